@@ -0,0 +1,83 @@
+use practices::random::XorShift;
+use practices::structure::dary_heap::DaryHeap;
+use std::collections::BinaryHeap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 200_000;
+const ROUNDS: u32 = 5;
+
+fn random_keys(n: usize) -> Vec<u64> {
+    let mut rng = XorShift::default();
+    (0..n).map(|_| rng.next_u64()).collect()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f();
+        best = best.min(start.elapsed());
+    }
+    best
+}
+
+// pushes everything, pops a tenth
+fn push_heavy<const D: usize>(keys: &[u64]) -> Duration {
+    time(|| {
+        let mut heap: DaryHeap<u64, D> = DaryHeap::new();
+        for &k in keys {
+            heap.push(k);
+        }
+        for _ in 0..keys.len() / 10 {
+            black_box(heap.pop());
+        }
+    })
+}
+
+// heapifies everything, pops all of it
+fn pop_heavy<const D: usize>(keys: &[u64]) -> Duration {
+    time(|| {
+        let mut heap: DaryHeap<u64, D> = DaryHeap::new().init(keys.to_vec());
+        while let Some(x) = heap.pop() {
+            black_box(x);
+        }
+    })
+}
+
+fn report(name: &str, d: Duration) {
+    println!("{:<28} {:>10.3} ms", name, d.as_secs_f64() * 1e3);
+}
+
+fn main() {
+    let keys = random_keys(N);
+
+    report(
+        "std BinaryHeap push-heavy",
+        time(|| {
+            let mut heap: BinaryHeap<u64> = BinaryHeap::new();
+            for &k in &keys {
+                heap.push(k);
+            }
+            for _ in 0..keys.len() / 10 {
+                black_box(heap.pop());
+            }
+        }),
+    );
+    report("dary_heap<2> push-heavy", push_heavy::<2>(&keys));
+    report("dary_heap<4> push-heavy", push_heavy::<4>(&keys));
+    report("dary_heap<8> push-heavy", push_heavy::<8>(&keys));
+
+    report(
+        "std BinaryHeap pop-heavy",
+        time(|| {
+            let mut heap = BinaryHeap::from(keys.to_vec());
+            while let Some(x) = heap.pop() {
+                black_box(x);
+            }
+        }),
+    );
+    report("dary_heap<2> pop-heavy", pop_heavy::<2>(&keys));
+    report("dary_heap<4> pop-heavy", pop_heavy::<4>(&keys));
+    report("dary_heap<8> pop-heavy", pop_heavy::<8>(&keys));
+}
@@ -0,0 +1,75 @@
+use practices::random::XorShift;
+use practices::sorting::dual_pivot_quick_sort::dual_pivot_quick_sort;
+use practices::sorting::intro_sort::intro_sort;
+use practices::sorting::pdq_sort::pdq_sort;
+use practices::sorting::quick_sort::quick_sort;
+use practices::sorting::three_way_quick_sort::three_way_quick_sort;
+use practices::sorting::tim_sort::tim_sort;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const N: usize = 100_000;
+const ROUNDS: u32 = 5;
+
+type Sort = fn(&mut [u64]);
+
+fn random_keys(n: usize, modulo: u64) -> Vec<u64> {
+    let mut rng = XorShift::default();
+    (0..n).map(|_| rng.next_u64() % modulo).collect()
+}
+
+fn inputs() -> Vec<(&'static str, Vec<u64>)> {
+    let n = N as u64;
+    vec![
+        ("random", random_keys(N, u64::MAX)),
+        ("duplicates", random_keys(N, 16)),
+        ("sorted", (0..n).collect()),
+        ("reversed", (0..n).rev().collect()),
+        ("organ-pipe", (0..n / 2).chain((0..n / 2).rev()).collect()),
+    ]
+}
+
+fn time(input: &[u64], sort: Sort) -> Duration {
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let mut xs = input.to_vec();
+        let start = Instant::now();
+        sort(&mut xs);
+        best = best.min(start.elapsed());
+        black_box(xs);
+    }
+    best
+}
+
+// `quick_sort` takes the first element as its pivot, it goes quadratic and
+// overflows the stack on the presorted inputs, so it only runs on the others
+fn main() {
+    let inputs = inputs();
+    let sorts: Vec<(&str, Sort, &[&str])> = vec![
+        ("quick_sort", quick_sort, &["random", "duplicates"]),
+        ("three_way_quick_sort", three_way_quick_sort, &[]),
+        ("dual_pivot_quick_sort", dual_pivot_quick_sort, &[]),
+        ("intro_sort", intro_sort, &[]),
+        ("pdq_sort", pdq_sort, &[]),
+        ("slice::sort_unstable", |xs| xs.sort_unstable(), &[]),
+        ("tim_sort", tim_sort, &[]),
+        ("slice::sort", |xs| xs.sort(), &[]),
+    ];
+
+    print!("{:<24}", "");
+    for (name, _) in &inputs {
+        print!(" {:>12}", name);
+    }
+    println!();
+    for (name, sort, only) in sorts {
+        print!("{:<24}", name);
+        for (input_name, input) in &inputs {
+            if only.is_empty() || only.contains(input_name) {
+                print!(" {:>9.3} ms", time(input, sort).as_secs_f64() * 1e3);
+            } else {
+                print!(" {:>12}", "-");
+            }
+        }
+        println!();
+    }
+}
@@ -2,5 +2,7 @@
 #![allow(clippy::needless_range_loop)]
 
 pub mod clrs;
+#[doc(hidden)]
+pub mod random;
 pub mod sorting;
 pub mod structure;
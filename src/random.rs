@@ -0,0 +1,74 @@
+// xorshift64 shared by the tests, the benchmarks and pdq sort's pattern
+// breaking, a fixed seed gives the same numbers on every run
+#[derive(Clone, Debug)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    // any seed works, it is scrambled with a splitmix64 step so the state is
+    // never zero and nearby seeds give unrelated streams
+    pub fn new(seed: u64) -> XorShift {
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        XorShift {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // slightly biased towards small values unless `n` is a power of two,
+    // which is fine for generating inputs
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+impl Default for XorShift {
+    fn default() -> Self {
+        XorShift::new(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let mut a = XorShift::new(42);
+        let mut b = XorShift::new(42);
+        let xs: Vec<u64> = (0..100).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..100).map(|_| b.next_u64()).collect();
+        assert_eq!(xs, ys);
+        assert!(xs.iter().all(|&x| x != 0));
+        assert_ne!(XorShift::default().next_u64(), XorShift::new(43).next_u64());
+    }
+
+    #[test]
+    fn test_below() {
+        let mut rng = XorShift::default();
+        let mut seen = [false; 10];
+        for _ in 0..1000 {
+            seen[rng.below(10)] = true;
+        }
+        assert!(seen.iter().all(|&x| x));
+    }
+
+    #[test]
+    fn test_small_seeds() {
+        let firsts: Vec<u64> = (0..64).map(|seed| XorShift::new(seed).next_u64()).collect();
+        for (i, &x) in firsts.iter().enumerate() {
+            assert_ne!(x, 0);
+            assert!(!firsts[..i].contains(&x));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+// McIlroy's adversary for quicksorts: values are decided lazily so that
+// whatever a sort picks as its pivot compares as small as possible
+
+use crate::sorting::probe::{Probe, SortStats};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+
+struct Adversary {
+    values: Vec<usize>,
+    gas: usize,
+    solid: usize,
+    candidate: usize,
+}
+
+impl Adversary {
+    fn new(n: usize) -> Adversary {
+        Adversary {
+            values: vec![n; n],
+            gas: n,
+            solid: 0,
+            candidate: 0,
+        }
+    }
+
+    fn freeze(&mut self, x: usize) {
+        self.values[x] = self.solid;
+        self.solid += 1;
+    }
+
+    fn freeze_if_gas(&mut self, x: usize) {
+        if self.values[x] == self.gas {
+            self.freeze(x);
+        }
+    }
+
+    fn compare(&mut self, x: usize, y: usize) -> Ordering {
+        if self.values[x] == self.gas && self.values[y] == self.gas {
+            if x == self.candidate {
+                self.freeze(x);
+            } else {
+                self.freeze(y);
+            }
+        }
+        if self.values[x] == self.gas {
+            self.candidate = x;
+        } else if self.values[y] == self.gas {
+            self.candidate = y;
+        }
+        self.values[x].cmp(&self.values[y])
+    }
+}
+
+// sorts `0..n` against the adversary, checks the result is ordered by the
+// values it settled on and returns the number of comparisons
+pub(crate) fn against_adversary<S>(n: usize, sort: S) -> usize
+where
+    S: FnOnce(&mut [usize], &mut dyn FnMut(&usize, &usize) -> Ordering, &mut SortStats),
+{
+    let mut adversary = Adversary::new(n);
+    let mut xs: Vec<usize> = (0..n).collect();
+    let mut stats = SortStats::new();
+    sort(&mut xs, &mut |a, b| adversary.compare(*a, *b), &mut stats);
+    let values: Vec<usize> = xs.iter().map(|&x| adversary.values[x]).collect();
+    assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    stats.comparisons
+}
+
+// follows the swaps to know which element sits where, and freezes both
+// ends of a range as the smallest keys left when it is partitioned, so a
+// sort taking its pivots from there leaves everything else on one side
+struct PivotFreezer<'a> {
+    adversary: &'a RefCell<Adversary>,
+    at: Vec<usize>,
+    stats: SortStats,
+}
+
+impl<'a> Probe for PivotFreezer<'a> {
+    fn compare(&mut self, i: usize, j: usize) {
+        self.stats.compare(i, j);
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.stats.swap(i, j);
+        self.at.swap(i, j);
+    }
+
+    fn partition(&mut self, lo: usize, hi: usize) {
+        let mut adversary = self.adversary.borrow_mut();
+        adversary.freeze_if_gas(self.at[lo]);
+        adversary.freeze_if_gas(self.at[hi]);
+    }
+}
+
+// like `against_adversary` for sorts that only move elements by swaps and
+// report their partitions, with the pivots at the ends of the range
+pub(crate) fn against_pivot_adversary<S>(n: usize, sort: S) -> usize
+where
+    S: FnOnce(&mut [usize], &mut dyn FnMut(&usize, &usize) -> Ordering, &mut dyn Probe),
+{
+    let adversary = RefCell::new(Adversary::new(n));
+    let mut xs: Vec<usize> = (0..n).collect();
+    let mut probe = PivotFreezer {
+        adversary: &adversary,
+        at: xs.clone(),
+        stats: SortStats::new(),
+    };
+    sort(
+        &mut xs,
+        &mut |a, b| adversary.borrow_mut().compare(*a, *b),
+        &mut probe,
+    );
+    let adversary = adversary.borrow();
+    let values: Vec<usize> = xs.iter().map(|&x| adversary.values[x]).collect();
+    assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    probe.stats.comparisons
+}
@@ -1,8 +1,28 @@
-pub fn bubble_sort<T: Ord + Copy>(xs: &mut [T]) {
-    for i in 0..xs.len() {
-        for j in 0..xs.len() - i - 1 {
-            if xs[j + 1] < xs[j] {
-                xs.swap(j, j + 1);
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn bubble_sort<T: Ord>(xs: &mut [T]) {
+    bubble_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn bubble_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    bubble_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn bubble_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    bubble_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn bubble_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    for i in 0..s.len() {
+        for j in 0..s.len() - i - 1 {
+            if s.less(j + 1, j) {
+                s.swap(j, j + 1);
             }
         }
     }
@@ -32,4 +52,11 @@ mod tests {
         bubble_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_stable() {
+        let mut xs = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+        bubble_sort_by_key(&mut xs, |x| x.0);
+        assert_eq!(xs, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
 }
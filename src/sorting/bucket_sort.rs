@@ -1,27 +1,36 @@
-use crate::sorting::insert_sort::insert_sort;
+use crate::sorting::insert_sort::insert_sort_probed;
+use crate::sorting::probe::{NoProbe, Probe};
+use std::mem;
 
 pub fn bucket_sort(xs: &mut [usize]) {
+    bucket_sort_probed(xs, &mut NoProbe)
+}
+
+pub fn bucket_sort_probed<P: Probe + ?Sized>(xs: &mut [usize], probe: &mut P) {
     if xs.is_empty() {
         return;
     }
     let total_size = xs.iter().max().unwrap() + 1;
     let bucket_step = 3;
-    let buckets_size = (total_size + bucket_step - 1) / bucket_step;
+    let buckets_size = total_size.div_ceil(bucket_step);
 
     let mut buckets: Vec<Vec<usize>> = vec![vec![]; buckets_size];
     for &x in xs.iter() {
         buckets[x / bucket_step].push(x);
     }
-    for i in 0..buckets_size {
-        insert_sort(&mut buckets[i]);
-    }
+    let capacity: usize = buckets.iter().map(|bucket| bucket.capacity()).sum();
+    probe.alloc(buckets_size * mem::size_of::<Vec<usize>>() + capacity * mem::size_of::<usize>());
     let mut j = 0;
     for bucket in buckets {
         for item in bucket {
+            probe.write(j);
             xs[j] = item;
             j += 1;
         }
     }
+    // every element is already inside its own bucket's range, so one
+    // insertion sort pass only moves elements within their buckets
+    insert_sort_probed(xs, |a, b| a.cmp(b), probe);
 }
 
 #[cfg(test)]
@@ -1,4 +1,11 @@
+use crate::sorting::probe::{NoProbe, Probe};
+use std::mem;
+
 pub fn counting_sort(xs: &mut [usize]) {
+    counting_sort_probed(xs, &mut NoProbe)
+}
+
+pub fn counting_sort_probed<P: Probe + ?Sized>(xs: &mut [usize], probe: &mut P) {
     if xs.len() <= 1 {
         return;
     }
@@ -6,12 +13,14 @@ pub fn counting_sort(xs: &mut [usize]) {
     let size = max + 1;
 
     let mut ys = vec![0; size];
+    probe.alloc(size * mem::size_of::<usize>());
     for &x in xs.iter() {
         ys[x] += 1;
     }
     let mut k = 0;
     for i in 0..size {
         for _ in 0..ys[i] {
+            probe.write(k);
             xs[k] = i;
             k += 1;
         }
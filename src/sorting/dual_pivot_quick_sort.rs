@@ -0,0 +1,227 @@
+use crate::sorting::heap_sort::heap_sort_range;
+use crate::sorting::insert_sort::insert_sort_range;
+use crate::sorting::intro_sort::log2;
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+const INSERTION_CUTOFF: usize = 16;
+
+pub fn dual_pivot_quick_sort<T: Ord>(xs: &mut [T]) {
+    dual_pivot_quick_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn dual_pivot_quick_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    dual_pivot_quick_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn dual_pivot_quick_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    dual_pivot_quick_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn dual_pivot_quick_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.len() <= 1 {
+        return;
+    }
+    let hi = s.len() - 1;
+    let depth = 2 * log2(s.len());
+    dual_pivot_sort(&mut s, 0, hi, depth);
+}
+
+// recurses into the two smaller of the three parts and loops on the
+// largest, so the stack stays O(log n) and the depth limit bounds the time
+fn dual_pivot_sort<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    mut lo: usize,
+    mut hi: usize,
+    mut depth: usize,
+) {
+    loop {
+        if hi - lo < INSERTION_CUTOFF {
+            insert_sort_range(s, lo, hi);
+            return;
+        }
+        if depth == 0 {
+            heap_sort_range(s, lo, hi);
+            return;
+        }
+        depth -= 1;
+
+        // pivots from the tertiles keep sorted and reversed input balanced
+        let third = (hi - lo + 1) / 3;
+        s.swap(lo, lo + third);
+        s.swap(hi, hi - third);
+        if s.less(hi, lo) {
+            s.swap(lo, hi);
+        }
+        s.probe().partition(lo, hi);
+        let (l, g) = partition_dual(s, lo, hi);
+
+        let left = if l > lo { Some((lo, l - 1)) } else { None };
+        let right = if g < hi { Some((g + 1, hi)) } else { None };
+        let middle = middle_part(s, lo, hi, l, g);
+        let mut parts = [left, middle, right];
+        let size = |part: &Option<(usize, usize)>| part.map_or(0, |(a, b)| b - a + 1);
+        let largest = (0..3).max_by_key(|&i| size(&parts[i])).unwrap();
+        parts.swap(largest, 2);
+
+        for &(a, b) in parts[..2].iter().flatten() {
+            dual_pivot_sort(s, a, b, depth);
+        }
+        match parts[2] {
+            Some((a, b)) => {
+                lo = a;
+                hi = b;
+            }
+            None => return,
+        }
+    }
+}
+
+// what is left to sort between the pivots at `l` and `g`
+fn middle_part<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+    l: usize,
+    g: usize,
+) -> Option<(usize, usize)> {
+    // with equal pivots the middle holds nothing but copies of them
+    if l + 1 >= g || s.compare(l, g) == Ordering::Equal {
+        return None;
+    }
+    let (a, b) = (l + 1, g - 1);
+    if b - a < (hi - lo + 1) * 2 / 3 {
+        return Some((a, b));
+    }
+    // a large middle is likely full of copies of the pivots, squeeze them out
+    let (lt, gt) = squeeze(s, l, g);
+    if lt >= gt {
+        None
+    } else {
+        Some((lt, gt))
+    }
+}
+
+// Yaroslavskiy's partition with the pivots at `lo` and `hi`, afterwards
+// they sit at `l` and `g` with `lo..l` less than the first, `l + 1..g`
+// between them and `g + 1..=hi` greater than the second
+fn partition_dual<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> (usize, usize) {
+    let mut l = lo + 1;
+    let mut g = hi - 1;
+    let mut k = l;
+    while k <= g {
+        if s.less(k, lo) {
+            s.swap(k, l);
+            l += 1;
+        } else if s.compare(k, hi) == Ordering::Greater {
+            while k < g && s.compare(g, hi) == Ordering::Greater {
+                g -= 1;
+            }
+            s.swap(k, g);
+            g -= 1;
+            if s.less(k, lo) {
+                s.swap(k, l);
+                l += 1;
+            }
+        }
+        k += 1;
+    }
+    l -= 1;
+    g += 1;
+    s.swap(lo, l);
+    s.swap(hi, g);
+    (l, g)
+}
+
+// moves the keys equal to either pivot to the ends of the middle part and
+// returns what is left between them
+fn squeeze<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    l: usize,
+    g: usize,
+) -> (usize, usize) {
+    let mut lt = l + 1;
+    let mut gt = g - 1;
+    let mut k = lt;
+    while k <= gt {
+        if s.compare(k, l) == Ordering::Equal {
+            s.swap(k, lt);
+            lt += 1;
+            k += 1;
+        } else if s.compare(k, g) == Ordering::Equal {
+            s.swap(k, gt);
+            gt -= 1;
+        } else {
+            k += 1;
+        }
+    }
+    (lt, gt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::adversary::against_pivot_adversary;
+    use crate::sorting::probe::SortStats;
+
+    #[test]
+    fn test_partition_dual() {
+        let mut xs = [2, 7, 1, 5, 9, 2, 3, 8, 0, 6];
+        let mut probe = NoProbe;
+        let mut s = Probed::new(&mut xs, |a: &i32, b: &i32| a.cmp(b), &mut probe);
+        let (l, g) = partition_dual(&mut s, 0, 9);
+        assert_eq!((xs[l], xs[g]), (2, 6));
+        assert!(xs[..l].iter().all(|&x| x < 2));
+        assert!(xs[l + 1..g].iter().all(|&x| (2..=6).contains(&x)));
+        assert!(xs[g + 1..].iter().all(|&x| x > 6));
+    }
+
+    #[test]
+    fn test_patterns() {
+        let n = if cfg!(miri) { 300 } else { 100_000 };
+        let sorted: Vec<usize> = (0..n).collect();
+        let reversed: Vec<usize> = (0..n).rev().collect();
+        let organ_pipe: Vec<usize> = (0..n / 2).chain((0..n / 2).rev()).collect();
+        let few_keys: Vec<usize> = (0..n).map(|i| i * 7919 % 3).collect();
+        let equal = vec![7; n];
+        for xs in [sorted, reversed, organ_pipe, few_keys, equal] {
+            let mut expected = xs.clone();
+            expected.sort_unstable();
+            let mut ys = xs;
+            dual_pivot_quick_sort(&mut ys);
+            assert_eq!(ys, expected);
+        }
+    }
+
+    #[test]
+    fn test_few_distinct_keys_are_linear() {
+        let n = if cfg!(miri) { 500 } else { 50_000 };
+        let mut xs: Vec<usize> = (0..n).map(|i| i * 7919 % 4).collect();
+        let mut stats = SortStats::new();
+        dual_pivot_quick_sort_probed(&mut xs, |a, b| a.cmp(b), &mut stats);
+        assert!(xs.windows(2).all(|w| w[0] <= w[1]));
+        assert!(
+            stats.comparisons < 8 * n,
+            "{} comparisons",
+            stats.comparisons
+        );
+    }
+
+    #[test]
+    fn test_adversary() {
+        let n = if cfg!(miri) { 200 } else { 4000 };
+        let comparisons = against_pivot_adversary(n, |xs, compare, stats| {
+            dual_pivot_quick_sort_probed(xs, compare, stats)
+        });
+        assert!(comparisons < 8 * n * log2(n), "{} comparisons", comparisons);
+    }
+}
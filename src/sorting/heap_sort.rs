@@ -1,56 +1,105 @@
-pub fn heap_sort<T: Ord + Copy>(xs: &mut [T]) {
-    if xs.len() <= 1 {
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn heap_sort<T: Ord>(xs: &mut [T]) {
+    heap_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn heap_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    heap_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn heap_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    heap_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn heap_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
+        return;
+    }
+    let hi = s.len() - 1;
+    heap_sort_range(&mut s, 0, hi);
+}
+
+// heap sorts `lo..=hi`, the heap is rooted at `lo` and every index below
+// is relative to it
+pub(crate) fn heap_sort_range<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) {
+    let n = hi - lo + 1;
+    if n <= 1 {
         return;
     }
-    build_max_heap(xs);
-    let mut heap_size = xs.len();
+    build_max_heap(s, lo, n);
+    let mut heap_size = n;
 
-    for i in (1..xs.len()).rev() {
-        xs.swap(0, i);
+    for i in (1..n).rev() {
+        s.swap(lo, lo + i);
         heap_size -= 1;
-        max_heapify_iterative(xs, 0, heap_size);
+        max_heapify_iterative(s, lo, 0, heap_size);
     }
 }
 
-fn build_max_heap<T: Ord + Copy>(xs: &mut [T]) {
-    for i in (0..=parent(xs.len() - 1)).rev() {
-        max_heapify_iterative(xs, i, xs.len());
+fn build_max_heap<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    base: usize,
+    n: usize,
+) {
+    for i in (0..=parent(n - 1)).rev() {
+        max_heapify_iterative(s, base, i, n);
     }
 }
 
 #[allow(dead_code)]
 // sink element recursively
-fn max_heapify_recursive<T: Ord + Copy>(xs: &mut [T], i: usize, heap_size: usize) {
+fn max_heapify_recursive<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    base: usize,
+    i: usize,
+    heap_size: usize,
+) {
     let l = left(i);
     let r = right(i);
     let mut largest = i;
-    if l < heap_size && xs[l] > xs[largest] {
+    if l < heap_size && s.compare(base + l, base + largest) == Ordering::Greater {
         largest = l;
     }
-    if r < heap_size && xs[r] > xs[largest] {
+    if r < heap_size && s.compare(base + r, base + largest) == Ordering::Greater {
         largest = r;
     }
     if largest != i {
-        xs.swap(i, largest);
-        max_heapify_recursive(xs, largest, heap_size);
+        s.swap(base + i, base + largest);
+        max_heapify_recursive(s, base, largest, heap_size);
     }
 }
 
 // sink element iteratively
-fn max_heapify_iterative<T: Ord + Copy>(xs: &mut [T], i: usize, heap_size: usize) {
+fn max_heapify_iterative<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    base: usize,
+    i: usize,
+    heap_size: usize,
+) {
     let mut i = i;
     while i < heap_size {
         let l = left(i);
         let r = right(i);
         let mut largest = i;
-        if l < heap_size && xs[l] > xs[largest] {
+        if l < heap_size && s.compare(base + l, base + largest) == Ordering::Greater {
             largest = l;
         }
-        if r < heap_size && xs[r] > xs[largest] {
+        if r < heap_size && s.compare(base + r, base + largest) == Ordering::Greater {
             largest = r;
         }
         if largest != i {
-            xs.swap(i, largest);
+            s.swap(base + i, base + largest);
             i = largest;
         } else {
             break;
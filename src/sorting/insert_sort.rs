@@ -1,12 +1,43 @@
-pub fn insert_sort<T: Ord + Copy>(xs: &mut [T]) {
-    for j in 1..xs.len() {
-        let key = xs[j];
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn insert_sort<T: Ord>(xs: &mut [T]) {
+    insert_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn insert_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    insert_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn insert_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    insert_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn insert_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
+        return;
+    }
+    let hi = s.len() - 1;
+    insert_sort_range(&mut s, 0, hi);
+}
+
+pub(crate) fn insert_sort_range<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) {
+    for j in lo + 1..=hi {
+        // swap the key down until its left neighbour is not greater
         let mut i = j;
-        while i > 0 && xs[i - 1] > key {
-            xs[i] = xs[i - 1];
+        while i > lo && s.compare(i - 1, i) == Ordering::Greater {
+            s.swap(i - 1, i);
             i -= 1;
         }
-        xs[i] = key;
     }
 }
 
@@ -34,4 +65,11 @@ mod tests {
         insert_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_stable() {
+        let mut xs = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+        insert_sort_by_key(&mut xs, |x| x.0);
+        assert_eq!(xs, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
 }
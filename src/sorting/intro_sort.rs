@@ -0,0 +1,159 @@
+use crate::sorting::heap_sort::heap_sort_range;
+use crate::sorting::insert_sort::insert_sort_range;
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use crate::sorting::quick_sort::partition;
+use std::cmp::Ordering;
+
+// ranges this short are left to insertion sort
+const INSERTION_CUTOFF: usize = 16;
+// ranges this long take the pivot from a ninther instead of three samples
+const NINTHER_THRESHOLD: usize = 128;
+
+pub fn intro_sort<T: Ord>(xs: &mut [T]) {
+    intro_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn intro_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    intro_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn intro_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    intro_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn intro_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.len() <= 1 {
+        return;
+    }
+    let hi = s.len() - 1;
+    let depth = 2 * log2(s.len());
+    intro_sort2(&mut s, 0, hi, depth);
+}
+
+pub(crate) fn log2(n: usize) -> usize {
+    (usize::BITS - 1 - n.leading_zeros()) as usize
+}
+
+// recurses into the smaller side and loops on the larger one, so the
+// stack stays O(log n) even before the depth limit kicks in
+fn intro_sort2<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    mut lo: usize,
+    mut hi: usize,
+    mut depth: usize,
+) {
+    loop {
+        if hi - lo < INSERTION_CUTOFF {
+            insert_sort_range(s, lo, hi);
+            return;
+        }
+        if depth == 0 {
+            heap_sort_range(s, lo, hi);
+            return;
+        }
+        depth -= 1;
+
+        let pivot = choose_pivot(s, lo, hi);
+        s.swap(lo, pivot);
+        s.probe().partition(lo, hi);
+        let j = partition(s, lo, hi);
+
+        if j - lo < hi - j {
+            if j > lo {
+                intro_sort2(s, lo, j - 1, depth);
+            }
+            lo = j + 1;
+        } else {
+            if j < hi {
+                intro_sort2(s, j + 1, hi, depth);
+            }
+            hi = j - 1;
+        }
+    }
+}
+
+pub(crate) fn choose_pivot<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> usize {
+    let mid = lo + (hi - lo) / 2;
+    if hi - lo + 1 < NINTHER_THRESHOLD {
+        return median_of_three(s, lo, mid, hi);
+    }
+    let step = (hi - lo) / 8;
+    let a = median_of_three(s, lo, lo + step, lo + 2 * step);
+    let b = median_of_three(s, mid - step, mid, mid + step);
+    let c = median_of_three(s, hi - 2 * step, hi - step, hi);
+    median_of_three(s, a, b, c)
+}
+
+// index of the median of three elements, compares only
+fn median_of_three<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    a: usize,
+    b: usize,
+    c: usize,
+) -> usize {
+    if s.less(b, a) {
+        if s.less(c, b) {
+            b
+        } else if s.less(c, a) {
+            c
+        } else {
+            a
+        }
+    } else if s.less(c, a) {
+        a
+    } else if s.less(c, b) {
+        c
+    } else {
+        b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::adversary::against_adversary;
+    use crate::sorting::quick_sort::quick_sort_probed;
+
+    #[test]
+    fn test_patterns() {
+        let n = if cfg!(miri) { 300 } else { 100_000 };
+        let sorted: Vec<usize> = (0..n).collect();
+        let reversed: Vec<usize> = (0..n).rev().collect();
+        let organ_pipe: Vec<usize> = (0..n / 2).chain((0..n / 2).rev()).collect();
+        let equal = vec![7; n];
+        for xs in [sorted, reversed, organ_pipe, equal] {
+            let mut expected = xs.clone();
+            expected.sort_unstable();
+            let mut ys = xs;
+            intro_sort(&mut ys);
+            assert_eq!(ys, expected);
+        }
+    }
+
+    #[test]
+    fn test_heap_sort_fallback() {
+        let n = if cfg!(miri) { 200 } else { 2000 };
+        let intro = against_adversary(n, |xs, compare, stats| {
+            intro_sort_probed(xs, compare, stats)
+        });
+        let quick = against_adversary(n, |xs, compare, stats| {
+            quick_sort_probed(xs, compare, stats)
+        });
+        // the adversary drives plain quick sort quadratic
+        assert!(quick > n * n / 4, "quick sort took {} comparisons", quick);
+        assert!(
+            intro < 8 * n * log2(n),
+            "intro sort took {} comparisons",
+            intro
+        );
+    }
+}
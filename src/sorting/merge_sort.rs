@@ -1,44 +1,98 @@
-pub fn merge_sort<T: Ord + Copy>(xs: &mut [T]) {
-    if xs.is_empty() {
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+use std::mem;
+
+pub fn merge_sort<T: Ord>(xs: &mut [T]) {
+    merge_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn merge_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    merge_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn merge_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    merge_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn merge_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
         return;
     }
-    recurisive_sort(xs, 0, xs.len() - 1)
+    let hi = s.len() - 1;
+    recurisive_sort(&mut s, 0, hi)
 }
 
-fn recurisive_sort<T: Ord + Copy>(xs: &mut [T], lo: usize, hi: usize) {
+fn recurisive_sort<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) {
     if hi <= lo {
         return;
     }
     let mid = lo + (hi - lo) / 2;
-    recurisive_sort(xs, lo, mid);
-    recurisive_sort(xs, mid + 1, hi);
-    merge(xs, lo, mid, hi);
+    recurisive_sort(s, lo, mid);
+    recurisive_sort(s, mid + 1, hi);
+    merge(s, lo, mid, hi);
 }
 
-fn merge<T: Ord + Copy>(xs: &mut [T], lo: usize, mid: usize, hi: usize) {
-    let mut temp = vec![];
-    temp.reserve_exact(hi - lo + 1);
-    for k in lo..=hi {
-        temp.push(xs[k])
-    }
+// decides the merged order with comparisons only, then moves elements into
+// place, so a panicking comparator leaves the slice untouched
+fn merge<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    mid: usize,
+    hi: usize,
+) {
+    s.probe().merge(lo, mid, hi);
+    let mut order = Vec::with_capacity(hi - lo + 1);
+    s.alloc(order.capacity() * mem::size_of::<usize>());
 
-    let mut i = 0;
-    let mut j = mid - lo + 1;
-    for k in lo..=hi {
-        if i > mid - lo {
-            xs[k] = temp[j];
+    let mut i = lo;
+    let mut j = mid + 1;
+    for _ in lo..=hi {
+        if i > mid {
+            order.push(j - lo);
             j += 1;
-        } else if j > hi - lo {
-            xs[k] = temp[i];
+        } else if j > hi {
+            order.push(i - lo);
             i += 1;
-        } else if temp[j] < temp[i] {
-            xs[k] = temp[j];
+        } else if s.less(j, i) {
+            order.push(j - lo);
             j += 1;
         } else {
-            xs[k] = temp[i];
+            order.push(i - lo);
             i += 1;
         }
     }
+
+    permute(s, lo, &mut order);
+}
+
+// puts the element at `lo + order[k]` into position `lo + k`, one cycle at a time
+// and leaves `order` as the identity
+pub(crate) fn permute<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    order: &mut [usize],
+) {
+    for start in 0..order.len() {
+        let mut k = start;
+        loop {
+            let next = order[k];
+            order[k] = k;
+            if next == start || next == k {
+                break;
+            }
+            s.swap(lo + k, lo + next);
+            k = next;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -72,4 +126,11 @@ mod tests {
         merge_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_stable() {
+        let mut xs = [(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')];
+        merge_sort_by_key(&mut xs, |x| x.0);
+        assert_eq!(xs, [(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]);
+    }
 }
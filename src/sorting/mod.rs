@@ -1,10 +1,23 @@
 pub mod bubble_sort;
 pub mod bucket_sort;
 pub mod counting_sort;
+pub mod dual_pivot_quick_sort;
 pub mod heap_sort;
 pub mod insert_sort;
+pub mod intro_sort;
 pub mod merge_sort;
+pub mod pdq_sort;
+pub mod probe;
 pub mod quick_sort;
 pub mod radix_sort;
 pub mod selection_sort;
 pub mod shell_sort;
+pub mod sorter;
+pub mod three_way_quick_sort;
+pub mod tim_sort;
+pub mod trace;
+
+#[cfg(test)]
+mod adversary;
+#[cfg(test)]
+mod panic_check;
@@ -0,0 +1,40 @@
+// runs a `_by` sort with a comparator that panics after a given number of
+// calls, then checks the slice still holds exactly the original elements
+
+use std::cmp::Ordering;
+use std::panic::{self, AssertUnwindSafe};
+
+pub(crate) fn strings(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("s{}", i * 37 % n)).collect()
+}
+
+pub(crate) fn check_panic_safety<S>(name: &str, sort_by: S)
+where
+    S: Fn(&mut [String], &mut dyn FnMut(&String, &String) -> Ordering),
+{
+    let original = strings(64);
+    let mut expected = original.clone();
+    expected.sort();
+
+    for &limit in &[0, 1, 7, 50, 200] {
+        let mut xs = original.clone();
+        let mut calls = 0;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            sort_by(&mut xs, &mut |a: &String, b: &String| {
+                calls += 1;
+                if calls > limit {
+                    panic!("comparator gave up after {} calls", limit);
+                }
+                a.cmp(b)
+            })
+        }));
+        assert!(result.is_err(), "{} did not panic at limit {}", name, limit);
+
+        xs.sort();
+        assert_eq!(
+            xs, expected,
+            "{} lost or duplicated elements at limit {}",
+            name, limit
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use crate::random::XorShift;
+use crate::sorting::heap_sort::heap_sort_range;
+use crate::sorting::insert_sort::insert_sort_range;
+use crate::sorting::intro_sort::choose_pivot;
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::{self, Ordering};
+
+// pattern-defeating quicksort after Orson Peters, ranges here are half open
+// `lo..hi` and the pivot of a range is kept at `lo` while partitioning
+
+const INSERTION_CUTOFF: usize = 20;
+// elements examined per block when partitioning
+const BLOCK: usize = 64;
+// partial insertion sort gives up after this many out of order pairs
+const MAX_STEPS: usize = 5;
+// and does not shift at all on shorter ranges
+const SHORTEST_SHIFTING: usize = 50;
+
+pub fn pdq_sort<T: Ord>(xs: &mut [T]) {
+    pdq_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn pdq_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    pdq_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn pdq_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    pdq_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn pdq_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    let len = s.len();
+    if len <= 1 || single_run(&mut s) {
+        return;
+    }
+    let limit = (usize::BITS - len.leading_zeros()) as usize;
+    pdq(&mut s, 0, len, limit, true);
+}
+
+// sorts the slice in linear time when it is one ascending or descending run
+fn single_run<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(s: &mut Probed<T, F, P>) -> bool {
+    let len = s.len();
+    let descending = s.less(1, 0);
+    let mut i = 2;
+    while i < len && s.less(i, i - 1) == descending {
+        i += 1;
+    }
+    if i < len {
+        return false;
+    }
+    if descending {
+        for j in 0..len / 2 {
+            s.swap(j, len - 1 - j);
+        }
+    }
+    true
+}
+
+fn pdq<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    mut lo: usize,
+    mut hi: usize,
+    mut limit: usize,
+    mut leftmost: bool,
+) {
+    loop {
+        let len = hi - lo;
+        if len < INSERTION_CUTOFF {
+            if len >= 2 {
+                insert_sort_range(s, lo, hi - 1);
+            }
+            return;
+        }
+        if limit == 0 {
+            heap_sort_range(s, lo, hi - 1);
+            return;
+        }
+
+        let pivot = choose_pivot(s, lo, hi - 1);
+        s.swap(lo, pivot);
+
+        // the pivot of the enclosing partition sits at `lo - 1` and nothing
+        // here is smaller, if it equals this pivot so does everything up to
+        // the first greater element
+        if !leftmost && !s.less(lo - 1, lo) {
+            lo = partition_equal(s, lo, hi);
+            continue;
+        }
+
+        s.probe().partition(lo, hi - 1);
+        let (mid, was_partitioned) = partition(s, lo, hi);
+        let left_len = mid - lo;
+        let right_len = hi - mid - 1;
+
+        if left_len < len / 8 || right_len < len / 8 {
+            // a bad split, shuffle both sides so the next pivots differ
+            limit -= 1;
+            break_patterns(s, lo, mid);
+            break_patterns(s, mid + 1, hi);
+        } else if was_partitioned
+            && partial_insertion_sort(s, lo, mid)
+            && partial_insertion_sort(s, mid + 1, hi)
+        {
+            return;
+        }
+
+        if left_len < right_len {
+            pdq(s, lo, mid, limit, leftmost);
+            lo = mid + 1;
+            leftmost = false;
+        } else {
+            pdq(s, mid + 1, hi, limit, false);
+            hi = mid;
+        }
+    }
+}
+
+// moves the elements less than the pivot at `lo` before the others and the
+// pivot between them, returns its new index and whether nothing had to move
+fn partition<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> (usize, bool) {
+    let mut l = lo + 1;
+    let mut r = hi;
+    while l < r && s.less(l, lo) {
+        l += 1;
+    }
+    while l < r && !s.less(r - 1, lo) {
+        r -= 1;
+    }
+    let was_partitioned = l >= r;
+    let mid = partition_in_blocks(s, lo, l, r) - 1;
+    s.swap(lo, mid);
+    (mid, was_partitioned)
+}
+
+// BlockQuicksort: each side first records which of its next `BLOCK`
+// elements are misplaced without branching on the comparisons, then the
+// misplaced pairs are swapped, returns where the greater elements begin
+fn partition_in_blocks<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    pivot: usize,
+    mut l: usize,
+    mut r: usize,
+) -> usize {
+    let mut offsets_l = [0u8; BLOCK];
+    let mut offsets_r = [0u8; BLOCK];
+    let (mut start_l, mut num_l) = (0, 0);
+    let (mut start_r, mut num_r) = (0, 0);
+
+    while r - l >= 2 * BLOCK {
+        if num_l == 0 {
+            start_l = 0;
+            for k in 0..BLOCK {
+                offsets_l[num_l] = k as u8;
+                num_l += !s.less(l + k, pivot) as usize;
+            }
+        }
+        if num_r == 0 {
+            start_r = 0;
+            for k in 0..BLOCK {
+                offsets_r[num_r] = k as u8;
+                num_r += s.less(r - 1 - k, pivot) as usize;
+            }
+        }
+
+        let n = cmp::min(num_l, num_r);
+        for m in 0..n {
+            let i = l + offsets_l[start_l + m] as usize;
+            let j = r - 1 - offsets_r[start_r + m] as usize;
+            s.swap(i, j);
+        }
+        start_l += n;
+        start_r += n;
+        num_l -= n;
+        num_r -= n;
+
+        if num_l == 0 {
+            l += BLOCK;
+        }
+        if num_r == 0 {
+            r -= BLOCK;
+        }
+    }
+
+    // fewer than two blocks are left, a plain pass finishes them
+    let mut m = l;
+    for k in l..r {
+        if s.less(k, pivot) {
+            s.swap(k, m);
+            m += 1;
+        }
+    }
+    m
+}
+
+// the pivot at `lo` is known to be the smallest key in `lo..hi`, gathers
+// the elements equal to it and returns where the greater ones begin
+fn partition_equal<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> usize {
+    let mut l = lo + 1;
+    let mut r = hi;
+    loop {
+        while l < r && !s.less(lo, l) {
+            l += 1;
+        }
+        while l < r && s.less(lo, r - 1) {
+            r -= 1;
+        }
+        if l >= r {
+            return l;
+        }
+        r -= 1;
+        s.swap(l, r);
+        l += 1;
+    }
+}
+
+// fixes up a range with only a few out of order pairs, gives up and
+// returns false once it sees more than `MAX_STEPS` of them
+fn partial_insertion_sort<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> bool {
+    let mut i = lo + 1;
+    for _ in 0..MAX_STEPS {
+        while i < hi && !s.less(i, i - 1) {
+            i += 1;
+        }
+        if i >= hi {
+            return true;
+        }
+        if hi - lo < SHORTEST_SHIFTING {
+            return false;
+        }
+        s.swap(i - 1, i);
+        // sink the smaller one left and float the larger one right
+        let mut j = i - 1;
+        while j > lo && s.less(j, j - 1) {
+            s.swap(j - 1, j);
+            j -= 1;
+        }
+        let mut j = i;
+        while j + 1 < hi && s.less(j + 1, j) {
+            s.swap(j, j + 1);
+            j += 1;
+        }
+    }
+    false
+}
+
+// swaps a few elements near the middle with pseudo random ones
+fn break_patterns<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) {
+    let len = hi - lo;
+    if len < 8 {
+        return;
+    }
+    let mut rng = XorShift::new(len as u64);
+    let mask = len.next_power_of_two() - 1;
+    let pos = len / 4 * 2;
+    for i in 0..3 {
+        let mut other = rng.next_u64() as usize & mask;
+        if other >= len {
+            other -= len;
+        }
+        s.swap(lo + pos - 1 + i, lo + other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::adversary::against_adversary;
+    use crate::sorting::probe::SortStats;
+
+    fn stats(xs: &mut [usize]) -> SortStats {
+        let mut stats = SortStats::new();
+        pdq_sort_probed(xs, |a, b| a.cmp(b), &mut stats);
+        assert!(xs.windows(2).all(|w| w[0] <= w[1]));
+        stats
+    }
+
+    #[test]
+    fn test_runs_are_linear() {
+        let n = if cfg!(miri) { 300 } else { 100_000 };
+        let mut sorted: Vec<usize> = (0..n).collect();
+        assert_eq!(stats(&mut sorted).comparisons, n - 1);
+
+        let mut reversed: Vec<usize> = (0..n).rev().collect();
+        let reversed = stats(&mut reversed);
+        assert_eq!(reversed.comparisons, n - 1);
+        assert_eq!(reversed.swaps, n / 2);
+
+        let mut equal = vec![3; n];
+        assert_eq!(stats(&mut equal).comparisons, n - 1);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let n = if cfg!(miri) { 500 } else { 100_000 };
+        let mut rng = XorShift::default();
+        let mut nearly_sorted: Vec<usize> = (0..n).collect();
+        for _ in 0..10 {
+            let (i, j) = (rng.below(n), rng.below(n));
+            nearly_sorted.swap(i, j);
+        }
+        let patterns: Vec<Vec<usize>> = vec![
+            (0..n).map(|_| rng.next_u64() as usize).collect(),
+            (0..n).map(|_| rng.below(8)).collect(),
+            (0..n / 2).chain((0..n / 2).rev()).collect(),
+            (0..n / 2).rev().chain(0..n / 2).collect(),
+            (0..n).map(|i| i % 100).collect(),
+            nearly_sorted,
+        ];
+        for xs in patterns {
+            let mut expected = xs.clone();
+            expected.sort_unstable();
+            let mut ys = xs;
+            pdq_sort(&mut ys);
+            assert_eq!(ys, expected);
+        }
+    }
+
+    #[test]
+    fn test_few_distinct_keys() {
+        let n = if cfg!(miri) { 500 } else { 50_000 };
+        let mut xs: Vec<usize> = (0..n).map(|i| i * 7919 % 4).collect();
+        let comparisons = stats(&mut xs).comparisons;
+        assert!(comparisons < 8 * n, "{} comparisons", comparisons);
+    }
+
+    #[test]
+    fn test_partition_in_blocks() {
+        let n = 1000;
+        let mut xs: Vec<usize> = (0..n).map(|i| i * 7919 % n).collect();
+        xs[0] = 400;
+        let mut probe = NoProbe;
+        let mut s = Probed::new(&mut xs, |a: &usize, b: &usize| a.cmp(b), &mut probe);
+        let (mid, was_partitioned) = partition(&mut s, 0, n);
+        assert!(!was_partitioned);
+        assert_eq!(xs[mid], 400);
+        assert!(xs[..mid].iter().all(|&x| x < 400));
+        assert!(xs[mid + 1..].iter().all(|&x| x >= 400));
+    }
+
+    #[test]
+    fn test_adversary() {
+        let n: usize = if cfg!(miri) { 200 } else { 2000 };
+        let log2 = (usize::BITS - n.leading_zeros()) as usize;
+        let comparisons =
+            against_adversary(n, |xs, compare, stats| pdq_sort_probed(xs, compare, stats));
+        assert!(comparisons < 8 * n * log2, "{} comparisons", comparisons);
+    }
+}
@@ -0,0 +1,183 @@
+use std::cmp::Ordering;
+
+// hook the algorithms report their primitive steps to, indices are
+// positions in the slice being sorted
+pub trait Probe {
+    fn compare(&mut self, _i: usize, _j: usize) {}
+
+    fn swap(&mut self, _i: usize, _j: usize) {}
+
+    fn write(&mut self, _i: usize) {}
+
+    fn alloc(&mut self, _bytes: usize) {}
+
+    // quick sort is about to partition `lo..=hi`
+    fn partition(&mut self, _lo: usize, _hi: usize) {}
+
+    // merge sort is about to merge `lo..=mid` with `mid + 1..=hi`
+    fn merge(&mut self, _lo: usize, _mid: usize, _hi: usize) {}
+
+    // shell sort starts a pass with gap `h`
+    fn gap(&mut self, _h: usize) {}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProbe;
+
+impl Probe for NoProbe {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SortStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub writes: usize,
+    pub alloc_bytes: usize,
+}
+
+impl SortStats {
+    pub fn new() -> SortStats {
+        SortStats::default()
+    }
+}
+
+impl Probe for SortStats {
+    fn compare(&mut self, _i: usize, _j: usize) {
+        self.comparisons += 1;
+    }
+
+    fn swap(&mut self, _i: usize, _j: usize) {
+        self.swaps += 1;
+    }
+
+    fn write(&mut self, _i: usize) {
+        self.writes += 1;
+    }
+
+    fn alloc(&mut self, bytes: usize) {
+        self.alloc_bytes += bytes;
+    }
+}
+
+// the slice and comparator of a comparison sort, every compare and swap
+// goes through here so the probe sees it
+pub(crate) struct Probed<'a, T, F, P: ?Sized> {
+    xs: &'a mut [T],
+    compare: F,
+    probe: &'a mut P,
+}
+
+impl<'a, T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized> Probed<'a, T, F, P> {
+    pub(crate) fn new(xs: &'a mut [T], compare: F, probe: &'a mut P) -> Probed<'a, T, F, P> {
+        Probed { xs, compare, probe }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    pub(crate) fn compare(&mut self, i: usize, j: usize) -> Ordering {
+        self.probe.compare(i, j);
+        (self.compare)(&self.xs[i], &self.xs[j])
+    }
+
+    pub(crate) fn less(&mut self, i: usize, j: usize) -> bool {
+        self.compare(i, j) == Ordering::Less
+    }
+
+    pub(crate) fn swap(&mut self, i: usize, j: usize) {
+        self.probe.swap(i, j);
+        self.xs.swap(i, j);
+    }
+
+    pub(crate) fn alloc(&mut self, bytes: usize) {
+        self.probe.alloc(bytes);
+    }
+
+    pub(crate) fn probe(&mut self) -> &mut P {
+        self.probe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::bubble_sort::bubble_sort_probed;
+    use crate::sorting::counting_sort::counting_sort_probed;
+    use crate::sorting::insert_sort::insert_sort_probed;
+    use crate::sorting::merge_sort::merge_sort_probed;
+
+    fn ascending(a: &usize, b: &usize) -> Ordering {
+        a.cmp(b)
+    }
+
+    #[test]
+    fn test_probed_reports() {
+        let mut xs = [3, 1, 2];
+        let mut stats = SortStats::new();
+        let mut probed = Probed::new(&mut xs, |a: &i32, b: &i32| a.cmp(b), &mut stats);
+        assert!(probed.less(1, 0));
+        assert_eq!(probed.compare(2, 2), Ordering::Equal);
+        probed.swap(0, 1);
+        probed.alloc(16);
+        assert_eq!(probed.len(), 3);
+        assert_eq!(xs, [1, 3, 2]);
+        assert_eq!(
+            stats,
+            SortStats {
+                comparisons: 2,
+                swaps: 1,
+                writes: 0,
+                alloc_bytes: 16
+            }
+        );
+    }
+
+    #[test]
+    fn test_insert_sort_stats() {
+        let mut sorted: Vec<usize> = (0..100).collect();
+        let mut stats = SortStats::new();
+        insert_sort_probed(&mut sorted, ascending, &mut stats);
+        assert_eq!(stats.comparisons, 99);
+        assert_eq!(stats.swaps, 0);
+
+        let mut reversed: Vec<usize> = (0..100).rev().collect();
+        let mut stats = SortStats::new();
+        insert_sort_probed(&mut reversed, ascending, &mut stats);
+        assert_eq!(stats.swaps, 100 * 99 / 2);
+        assert_eq!(stats.alloc_bytes, 0);
+    }
+
+    #[test]
+    fn test_bubble_sort_stats() {
+        let mut xs: Vec<usize> = (0..50).map(|i| i * 7 % 50).collect();
+        let mut stats = SortStats::new();
+        bubble_sort_probed(&mut xs, ascending, &mut stats);
+        assert_eq!(stats.comparisons, 50 * 49 / 2);
+    }
+
+    #[test]
+    fn test_merge_sort_stats() {
+        let mut xs: Vec<usize> = (0..64).rev().collect();
+        let mut stats = SortStats::new();
+        merge_sort_probed(&mut xs, ascending, &mut stats);
+        // a reversed run of 2^k always takes the right half first
+        assert_eq!(stats.comparisons, 64 * 6 / 2);
+        // one order index per element per level
+        assert_eq!(stats.alloc_bytes, 64 * 6 * std::mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_counting_sort_stats() {
+        let mut xs = [3, 0, 3, 1];
+        let mut stats = SortStats::new();
+        counting_sort_probed(&mut xs, &mut stats);
+        assert_eq!(xs, [0, 1, 3, 3]);
+        assert_eq!(stats.comparisons, 0);
+        assert_eq!(stats.writes, 4);
+        assert_eq!(stats.alloc_bytes, 4 * std::mem::size_of::<usize>());
+    }
+}
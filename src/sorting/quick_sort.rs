@@ -1,34 +1,67 @@
-pub fn quick_sort<T: Ord + Copy>(xs: &mut [T]) {
-    if xs.is_empty() {
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn quick_sort<T: Ord>(xs: &mut [T]) {
+    quick_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn quick_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    quick_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn quick_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    quick_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn quick_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
         return;
     }
-    quick_sort2(xs, 0, xs.len() - 1);
+    let high = s.len() - 1;
+    quick_sort2(&mut s, 0, high);
 }
 
-fn quick_sort2<T: Ord + Copy>(xs: &mut [T], low: usize, high: usize) {
+fn quick_sort2<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    low: usize,
+    high: usize,
+) {
     if low >= high {
         return;
     }
-    let j = partition(xs, low, high);
-    quick_sort2(xs, low, j - 1);
-    quick_sort2(xs, j + 1, high);
+    s.probe().partition(low, high);
+    let j = partition(s, low, high);
+    // the pivot may land on `low`, where `j - 1` would underflow
+    if j > low {
+        quick_sort2(s, low, j - 1);
+    }
+    quick_sort2(s, j + 1, high);
 }
 
-fn partition<T: Ord + Copy>(xs: &mut [T], low: usize, high: usize) -> usize {
-    let v = xs[low];
+pub(crate) fn partition<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    low: usize,
+    high: usize,
+) -> usize {
+    // the pivot stays at `low` until the final swap
     let mut i = low;
     let mut j = high + 1;
     loop {
         loop {
             i += 1;
-            if !(i < high && xs[i] < v) {
+            if !(i < high && s.less(i, low)) {
                 break;
             }
         }
 
         loop {
             j -= 1;
-            if !(j > low && xs[j] > v) {
+            if !(j > low && s.compare(j, low) == Ordering::Greater) {
                 break;
             }
         }
@@ -37,10 +70,10 @@ fn partition<T: Ord + Copy>(xs: &mut [T], low: usize, high: usize) -> usize {
             break;
         }
 
-        xs.swap(i, j);
+        s.swap(i, j);
     }
 
-    xs.swap(low, j);
+    s.swap(low, j);
     return j;
 }
 
@@ -68,4 +101,11 @@ mod tests {
         quick_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_sorted() {
+        let mut xs = [1, 2, 3, 4, 5];
+        quick_sort(&mut xs);
+        assert_eq!(xs, [1, 2, 3, 4, 5]);
+    }
 }
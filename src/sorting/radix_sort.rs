@@ -1,10 +1,21 @@
+use crate::sorting::probe::{NoProbe, Probe};
+use std::mem;
+
 pub fn radix_sort(xs: &mut [usize]) {
+    radix_sort_probed(xs, &mut NoProbe)
+}
+
+pub fn radix_sort_probed<P: Probe + ?Sized>(xs: &mut [usize], probe: &mut P) {
     if xs.is_empty() {
         return;
     }
     let radix: usize = 10;
     let &max = xs.iter().max().unwrap();
-    let k = (max as f64).log(radix as f64).ceil() as u32;
+    // number of digits in `max`, a float log is off by one on powers of the radix
+    let mut k: u32 = 1;
+    while max / radix.pow(k) > 0 {
+        k += 1;
+    }
 
     for i in 1..=k {
         let mut buckets: Vec<Vec<usize>> = vec![vec![]; radix];
@@ -14,8 +25,13 @@ pub fn radix_sort(xs: &mut [usize]) {
             buckets[i].push(x);
         }
 
-        let mut flattened = buckets.into_iter().flatten().collect::<Vec<usize>>();
-        xs.swap_with_slice(&mut flattened[..]);
+        let capacity: usize = buckets.iter().map(|bucket| bucket.capacity()).sum();
+        probe.alloc(radix * mem::size_of::<Vec<usize>>() + capacity * mem::size_of::<usize>());
+
+        for (j, x) in buckets.into_iter().flatten().enumerate() {
+            probe.write(j);
+            xs[j] = x;
+        }
     }
 }
 
@@ -43,4 +59,11 @@ mod tests {
         radix_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_power_of_radix() {
+        let mut xs = [10, 5, 100, 1, 0];
+        radix_sort(&mut xs);
+        assert_eq!(xs, [0, 1, 5, 10, 100]);
+    }
 }
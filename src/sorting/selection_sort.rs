@@ -1,16 +1,36 @@
-pub fn selection_sort<T: Ord + Copy>(xs: &mut [T]) {
-    if xs.is_empty() {
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn selection_sort<T: Ord>(xs: &mut [T]) {
+    selection_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn selection_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    selection_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn selection_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    selection_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn selection_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
         return;
     }
-    for j in 0..xs.len() - 1 {
+    for j in 0..s.len() - 1 {
         let mut min = j;
-        for i in j..xs.len() {
-            if xs[i] < xs[min] {
+        for i in j..s.len() {
+            if s.less(i, min) {
                 min = i;
             }
         }
         if min != j {
-            xs.swap(min, j);
+            s.swap(min, j);
         }
     }
 }
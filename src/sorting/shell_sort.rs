@@ -1,5 +1,25 @@
-pub fn shell_sort<T: Ord + Copy>(xs: &mut [T]) {
-    if xs.is_empty() {
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+pub fn shell_sort<T: Ord>(xs: &mut [T]) {
+    shell_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn shell_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    shell_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn shell_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    shell_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn shell_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.is_empty() {
         return;
     }
     const STEP: usize = 3;
@@ -9,17 +29,19 @@ pub fn shell_sort<T: Ord + Copy>(xs: &mut [T]) {
         h = temp;
         temp = STEP * temp + 1;
 
-        if temp > xs.len() {
+        if temp > s.len() {
             break;
         }
     }
 
     while h >= 1 {
-        for i in h..xs.len() {
-            for j in (h..=i).step_by(h) {
-                if xs[j] < xs[j - h] {
-                    xs.swap(j, j - h);
-                }
+        s.probe().gap(h);
+        // insertion sort on every h-th element
+        for i in h..s.len() {
+            let mut j = i;
+            while j >= h && s.less(j, j - h) {
+                s.swap(j, j - h);
+                j -= h;
             }
         }
         h /= STEP;
@@ -50,4 +72,13 @@ mod tests {
         shell_sort(&mut xs);
         assert_eq!(xs, [4]);
     }
+
+    #[test]
+    fn test_long() {
+        let mut xs: Vec<i32> = (0..200).map(|i| i * 7919 % 211).collect();
+        let mut expected = xs.clone();
+        expected.sort();
+        shell_sort(&mut xs);
+        assert_eq!(xs, expected);
+    }
 }
@@ -0,0 +1,403 @@
+use crate::sorting::bubble_sort::{bubble_sort, bubble_sort_by, bubble_sort_probed};
+use crate::sorting::bucket_sort::{bucket_sort, bucket_sort_probed};
+use crate::sorting::counting_sort::{counting_sort, counting_sort_probed};
+use crate::sorting::dual_pivot_quick_sort::{
+    dual_pivot_quick_sort, dual_pivot_quick_sort_by, dual_pivot_quick_sort_probed,
+};
+use crate::sorting::heap_sort::{heap_sort, heap_sort_by, heap_sort_probed};
+use crate::sorting::insert_sort::{insert_sort, insert_sort_by, insert_sort_probed};
+use crate::sorting::intro_sort::{intro_sort, intro_sort_by, intro_sort_probed};
+use crate::sorting::merge_sort::{merge_sort, merge_sort_by, merge_sort_probed};
+use crate::sorting::pdq_sort::{pdq_sort, pdq_sort_by, pdq_sort_probed};
+use crate::sorting::probe::{Probe, SortStats};
+use crate::sorting::quick_sort::{quick_sort, quick_sort_by, quick_sort_probed};
+use crate::sorting::radix_sort::{radix_sort, radix_sort_probed};
+use crate::sorting::selection_sort::{selection_sort, selection_sort_by, selection_sort_probed};
+use crate::sorting::shell_sort::{shell_sort, shell_sort_by, shell_sort_probed};
+use crate::sorting::three_way_quick_sort::{
+    three_way_quick_sort, three_way_quick_sort_by, three_way_quick_sort_probed,
+};
+use crate::sorting::tim_sort::{tim_sort, tim_sort_by, tim_sort_probed};
+use std::cmp::Ordering;
+
+// one value per algorithm so tests and benchmarks can walk all of them,
+// the integer sorts only implement `Sorter<usize>` since they never compare
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Complexity {
+    pub best: &'static str,
+    pub average: &'static str,
+    pub worst: &'static str,
+    pub space: &'static str,
+}
+
+pub trait Sorter<T> {
+    fn name(&self) -> &'static str;
+
+    fn is_stable(&self) -> bool;
+
+    fn is_in_place(&self) -> bool;
+
+    fn complexity(&self) -> Complexity;
+
+    fn sort(&self, xs: &mut [T]);
+
+    fn sort_probed(&self, xs: &mut [T], probe: &mut dyn Probe);
+
+    fn sort_with_stats(&self, xs: &mut [T]) -> SortStats {
+        let mut stats = SortStats::new();
+        self.sort_probed(xs, &mut stats);
+        stats
+    }
+}
+
+pub trait ComparisonSorter<T>: Sorter<T> {
+    fn sort_by(&self, xs: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering);
+
+    fn sort_by_probed(
+        &self,
+        xs: &mut [T],
+        compare: &mut dyn FnMut(&T, &T) -> Ordering,
+        probe: &mut dyn Probe,
+    );
+}
+
+macro_rules! comparison_sorter {
+    ($ty:ident, $name:expr, $sort:ident, $sort_by:ident, $probed:ident,
+     stable: $stable:expr, in_place: $in_place:expr,
+     best: $best:expr, average: $average:expr, worst: $worst:expr, space: $space:expr) => {
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $ty;
+
+        impl<T: Ord> Sorter<T> for $ty {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn is_stable(&self) -> bool {
+                $stable
+            }
+
+            fn is_in_place(&self) -> bool {
+                $in_place
+            }
+
+            fn complexity(&self) -> Complexity {
+                Complexity {
+                    best: $best,
+                    average: $average,
+                    worst: $worst,
+                    space: $space,
+                }
+            }
+
+            fn sort(&self, xs: &mut [T]) {
+                $sort(xs)
+            }
+
+            fn sort_probed(&self, xs: &mut [T], probe: &mut dyn Probe) {
+                $probed(xs, |a: &T, b: &T| a.cmp(b), probe)
+            }
+        }
+
+        impl<T: Ord> ComparisonSorter<T> for $ty {
+            fn sort_by(&self, xs: &mut [T], compare: &mut dyn FnMut(&T, &T) -> Ordering) {
+                $sort_by(xs, compare)
+            }
+
+            fn sort_by_probed(
+                &self,
+                xs: &mut [T],
+                compare: &mut dyn FnMut(&T, &T) -> Ordering,
+                probe: &mut dyn Probe,
+            ) {
+                $probed(xs, compare, probe)
+            }
+        }
+    };
+}
+
+macro_rules! integer_sorter {
+    ($ty:ident, $name:expr, $sort:ident, $probed:ident,
+     stable: $stable:expr, in_place: $in_place:expr,
+     best: $best:expr, average: $average:expr, worst: $worst:expr, space: $space:expr) => {
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $ty;
+
+        impl Sorter<usize> for $ty {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn is_stable(&self) -> bool {
+                $stable
+            }
+
+            fn is_in_place(&self) -> bool {
+                $in_place
+            }
+
+            fn complexity(&self) -> Complexity {
+                Complexity {
+                    best: $best,
+                    average: $average,
+                    worst: $worst,
+                    space: $space,
+                }
+            }
+
+            fn sort(&self, xs: &mut [usize]) {
+                $sort(xs)
+            }
+
+            fn sort_probed(&self, xs: &mut [usize], probe: &mut dyn Probe) {
+                $probed(xs, probe)
+            }
+        }
+    };
+}
+
+comparison_sorter!(BubbleSort, "bubble_sort", bubble_sort, bubble_sort_by, bubble_sort_probed,
+    stable: true, in_place: true,
+    best: "O(n^2)", average: "O(n^2)", worst: "O(n^2)", space: "O(1)");
+comparison_sorter!(DualPivotQuickSort, "dual_pivot_quick_sort", dual_pivot_quick_sort,
+    dual_pivot_quick_sort_by, dual_pivot_quick_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n log n)", average: "O(n log n)", worst: "O(n log n)", space: "O(log n)");
+comparison_sorter!(HeapSort, "heap_sort", heap_sort, heap_sort_by, heap_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n log n)", average: "O(n log n)", worst: "O(n log n)", space: "O(1)");
+comparison_sorter!(InsertSort, "insert_sort", insert_sort, insert_sort_by, insert_sort_probed,
+    stable: true, in_place: true,
+    best: "O(n)", average: "O(n^2)", worst: "O(n^2)", space: "O(1)");
+comparison_sorter!(IntroSort, "intro_sort", intro_sort, intro_sort_by, intro_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n log n)", average: "O(n log n)", worst: "O(n log n)", space: "O(log n)");
+comparison_sorter!(MergeSort, "merge_sort", merge_sort, merge_sort_by, merge_sort_probed,
+    stable: true, in_place: false,
+    best: "O(n log n)", average: "O(n log n)", worst: "O(n log n)", space: "O(n)");
+comparison_sorter!(PdqSort, "pdq_sort", pdq_sort, pdq_sort_by, pdq_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n)", average: "O(n log n)", worst: "O(n log n)", space: "O(log n)");
+comparison_sorter!(QuickSort, "quick_sort", quick_sort, quick_sort_by, quick_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n log n)", average: "O(n log n)", worst: "O(n^2)", space: "O(n)");
+comparison_sorter!(SelectionSort, "selection_sort", selection_sort, selection_sort_by, selection_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n^2)", average: "O(n^2)", worst: "O(n^2)", space: "O(1)");
+comparison_sorter!(ShellSort, "shell_sort", shell_sort, shell_sort_by, shell_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n log n)", average: "O(n^1.25)", worst: "O(n^1.5)", space: "O(1)");
+comparison_sorter!(ThreeWayQuickSort, "three_way_quick_sort", three_way_quick_sort,
+    three_way_quick_sort_by, three_way_quick_sort_probed,
+    stable: false, in_place: true,
+    best: "O(n)", average: "O(n log n)", worst: "O(n^2)", space: "O(log n)");
+comparison_sorter!(TimSort, "tim_sort", tim_sort, tim_sort_by, tim_sort_probed,
+    stable: true, in_place: false,
+    best: "O(n)", average: "O(n log n)", worst: "O(n log n)", space: "O(n)");
+
+// k is the largest value, d the number of decimal digits in it
+integer_sorter!(BucketSort, "bucket_sort", bucket_sort, bucket_sort_probed,
+    stable: true, in_place: false,
+    best: "O(n + k)", average: "O(n + k)", worst: "O(n + k)", space: "O(n + k)");
+// rebuilds the values from their counts, so there is nothing to keep stable
+integer_sorter!(CountingSort, "counting_sort", counting_sort, counting_sort_probed,
+    stable: false, in_place: false,
+    best: "O(n + k)", average: "O(n + k)", worst: "O(n + k)", space: "O(k)");
+integer_sorter!(RadixSort, "radix_sort", radix_sort, radix_sort_probed,
+    stable: true, in_place: false,
+    best: "O(d n)", average: "O(d n)", worst: "O(d n)", space: "O(n)");
+
+pub fn comparison_sorters<T: Ord>() -> Vec<Box<dyn ComparisonSorter<T>>> {
+    vec![
+        Box::new(BubbleSort),
+        Box::new(DualPivotQuickSort),
+        Box::new(HeapSort),
+        Box::new(InsertSort),
+        Box::new(IntroSort),
+        Box::new(MergeSort),
+        Box::new(PdqSort),
+        Box::new(QuickSort),
+        Box::new(SelectionSort),
+        Box::new(ShellSort),
+        Box::new(ThreeWayQuickSort),
+        Box::new(TimSort),
+    ]
+}
+
+// every algorithm, including the ones that only sort integers
+pub fn sorters() -> Vec<Box<dyn Sorter<usize>>> {
+    let mut all: Vec<Box<dyn Sorter<usize>>> = vec![];
+    for sorter in comparison_sorters::<usize>() {
+        all.push(sorter);
+    }
+    all.push(Box::new(BucketSort));
+    all.push(Box::new(CountingSort));
+    all.push(Box::new(RadixSort));
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::sorting::bubble_sort::bubble_sort_by_key;
+    use crate::sorting::dual_pivot_quick_sort::dual_pivot_quick_sort_by_key;
+    use crate::sorting::heap_sort::heap_sort_by_key;
+    use crate::sorting::insert_sort::insert_sort_by_key;
+    use crate::sorting::intro_sort::intro_sort_by_key;
+    use crate::sorting::merge_sort::merge_sort_by_key;
+    use crate::sorting::panic_check::{check_panic_safety, strings};
+    use crate::sorting::pdq_sort::pdq_sort_by_key;
+    use crate::sorting::quick_sort::quick_sort_by_key;
+    use crate::sorting::selection_sort::selection_sort_by_key;
+    use crate::sorting::shell_sort::shell_sort_by_key;
+    use crate::sorting::three_way_quick_sort::three_way_quick_sort_by_key;
+    use crate::sorting::tim_sort::tim_sort_by_key;
+
+    type SortByKey = fn(&mut [i32], fn(&i32) -> i32);
+
+    fn random(n: usize, modulo: usize) -> Vec<usize> {
+        let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+        (0..n).map(|_| rng.below(modulo)).collect()
+    }
+
+    #[test]
+    fn test_registry() {
+        let names: Vec<&str> = sorters().iter().map(|s| s.name()).collect();
+        assert_eq!(names.len(), 15);
+        let mut unique = names.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+        assert!(names.contains(&"radix_sort"));
+    }
+
+    #[test]
+    fn test_every_sorter_sorts() {
+        for n in [0, 1, 2, 17, 300] {
+            let xs = random(n, 1000);
+            let mut expected = xs.clone();
+            expected.sort_unstable();
+            for sorter in sorters() {
+                let mut ys = xs.clone();
+                sorter.sort(&mut ys);
+                assert_eq!(ys, expected, "{} with n = {}", sorter.name(), n);
+            }
+        }
+        for sorter in sorters() {
+            let mut ys = vec![10, 1, 100, 0, 1];
+            sorter.sort(&mut ys);
+            assert_eq!(ys, [0, 1, 1, 10, 100], "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_sort_by() {
+        let xs: Vec<String> = random(200, 50).iter().map(|x| x.to_string()).collect();
+        let mut expected = xs.clone();
+        expected.sort_by(|a, b| b.cmp(a));
+        for sorter in comparison_sorters::<String>() {
+            let mut ys = xs.clone();
+            sorter.sort_by(&mut ys, &mut |a, b| b.cmp(a));
+            assert_eq!(ys, expected, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        // the `_by_key` wrappers are not part of the registry
+        let sorts: Vec<(&str, SortByKey)> = vec![
+            ("bubble_sort", bubble_sort_by_key as SortByKey),
+            ("dual_pivot_quick_sort", dual_pivot_quick_sort_by_key),
+            ("heap_sort", heap_sort_by_key),
+            ("insert_sort", insert_sort_by_key),
+            ("intro_sort", intro_sort_by_key),
+            ("merge_sort", merge_sort_by_key),
+            ("pdq_sort", pdq_sort_by_key),
+            ("quick_sort", quick_sort_by_key),
+            ("selection_sort", selection_sort_by_key),
+            ("shell_sort", shell_sort_by_key),
+            ("three_way_quick_sort", three_way_quick_sort_by_key),
+            ("tim_sort", tim_sort_by_key),
+        ];
+        assert_eq!(sorts.len(), comparison_sorters::<i32>().len());
+        for (name, sort) in sorts {
+            let mut xs = [-4, 5, 1, -2, 3];
+            sort(&mut xs, |x| x.abs());
+            assert_eq!(xs, [1, -2, 3, -4, 5], "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_strings() {
+        let xs = strings(100);
+        let mut expected = xs.clone();
+        expected.sort();
+        for sorter in comparison_sorters::<String>() {
+            let mut ys = xs.clone();
+            sorter.sort(&mut ys);
+            assert_eq!(ys, expected, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_panic_safety() {
+        for sorter in comparison_sorters::<String>() {
+            check_panic_safety(sorter.name(), |xs, compare| sorter.sort_by(xs, compare));
+        }
+    }
+
+    #[test]
+    fn test_stable_flag() {
+        // (key, original position), sorting on the key alone
+        let xs: Vec<(usize, usize)> = random(200, 8).into_iter().zip(0..).collect();
+        let mut expected = xs.clone();
+        expected.sort_by_key(|x| x.0);
+        for sorter in comparison_sorters::<(usize, usize)>() {
+            let mut ys = xs.clone();
+            sorter.sort_by(&mut ys, &mut |a, b| a.0.cmp(&b.0));
+            if sorter.is_stable() {
+                assert_eq!(ys, expected, "{} claims to be stable", sorter.name());
+            } else {
+                let keys: Vec<usize> = ys.iter().map(|x| x.0).collect();
+                assert!(keys.windows(2).all(|w| w[0] <= w[1]), "{}", sorter.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_stats_match_in_place_flag() {
+        let xs = random(500, 1000);
+        for sorter in sorters() {
+            let mut ys = xs.clone();
+            let stats = sorter.sort_with_stats(&mut ys);
+            assert!(ys.windows(2).all(|w| w[0] <= w[1]), "{}", sorter.name());
+            assert!(stats.swaps + stats.writes > 0, "{}", sorter.name());
+            assert_eq!(
+                stats.alloc_bytes == 0,
+                sorter.is_in_place(),
+                "{} allocated {} bytes",
+                sorter.name(),
+                stats.alloc_bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_sort_by_probed() {
+        let mut xs = random(100, 1000);
+        let mut calls = 0;
+        let mut stats = SortStats::new();
+        MergeSort.sort_by_probed(
+            &mut xs,
+            &mut |a: &usize, b: &usize| {
+                calls += 1;
+                b.cmp(a)
+            },
+            &mut stats,
+        );
+        assert!(xs.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(stats.comparisons, calls);
+    }
+}
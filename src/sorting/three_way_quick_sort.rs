@@ -0,0 +1,128 @@
+use crate::sorting::insert_sort::insert_sort_range;
+use crate::sorting::intro_sort::choose_pivot;
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::Ordering;
+
+const INSERTION_CUTOFF: usize = 16;
+
+pub fn three_way_quick_sort<T: Ord>(xs: &mut [T]) {
+    three_way_quick_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn three_way_quick_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    three_way_quick_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn three_way_quick_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    three_way_quick_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn three_way_quick_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    if s.len() <= 1 {
+        return;
+    }
+    let hi = s.len() - 1;
+    three_way_sort(&mut s, 0, hi);
+}
+
+fn three_way_sort<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    mut lo: usize,
+    mut hi: usize,
+) {
+    loop {
+        if hi - lo < INSERTION_CUTOFF {
+            insert_sort_range(s, lo, hi);
+            return;
+        }
+        let pivot = choose_pivot(s, lo, hi);
+        s.swap(lo, pivot);
+        s.probe().partition(lo, hi);
+        let (lt, gt) = partition3(s, lo, hi);
+
+        // the keys equal to the pivot are done, recurse into the smaller side
+        if lt - lo < hi - gt {
+            if lt > lo {
+                three_way_sort(s, lo, lt - 1);
+            }
+            if gt == hi {
+                return;
+            }
+            lo = gt + 1;
+        } else {
+            if gt < hi {
+                three_way_sort(s, gt + 1, hi);
+            }
+            if lt == lo {
+                return;
+            }
+            hi = lt - 1;
+        }
+    }
+}
+
+// Dijkstra's fat pivot partition with the pivot at `lo`, afterwards
+// `lo..lt` is less than the pivot, `lt..=gt` equal and `gt + 1..=hi` greater,
+// `xs[lt]` always holds a key equal to the pivot so it is compared against
+fn partition3<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    hi: usize,
+) -> (usize, usize) {
+    let mut lt = lo;
+    let mut i = lo + 1;
+    let mut gt = hi;
+    while i <= gt {
+        match s.compare(i, lt) {
+            Ordering::Less => {
+                s.swap(lt, i);
+                lt += 1;
+                i += 1;
+            }
+            Ordering::Greater => {
+                s.swap(i, gt);
+                gt -= 1;
+            }
+            Ordering::Equal => i += 1,
+        }
+    }
+    (lt, gt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::probe::SortStats;
+
+    #[test]
+    fn test_partition3() {
+        let mut xs = [3, 5, 3, 1, 3, 9, 0, 3];
+        let mut probe = NoProbe;
+        let mut s = Probed::new(&mut xs, |a: &i32, b: &i32| a.cmp(b), &mut probe);
+        let (lt, gt) = partition3(&mut s, 0, 7);
+        assert_eq!((lt, gt), (2, 5));
+        assert!(xs[..2].iter().all(|&x| x < 3));
+        assert!(xs[2..=5].iter().all(|&x| x == 3));
+        assert!(xs[6..].iter().all(|&x| x > 3));
+    }
+
+    #[test]
+    fn test_few_distinct_keys_are_linear() {
+        let n = if cfg!(miri) { 500 } else { 50_000 };
+        let mut xs: Vec<usize> = (0..n).map(|i| i * 7919 % 4).collect();
+        let mut stats = SortStats::new();
+        three_way_quick_sort_probed(&mut xs, |a, b| a.cmp(b), &mut stats);
+        assert!(xs.windows(2).all(|w| w[0] <= w[1]));
+        // one fat partition per distinct key, each pass is linear
+        assert!(
+            stats.comparisons < 4 * n,
+            "{} comparisons",
+            stats.comparisons
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use crate::sorting::merge_sort::permute;
+use crate::sorting::probe::{NoProbe, Probe, Probed};
+use std::cmp::{self, Ordering};
+use std::mem;
+
+// natural merge sort in the style of TimSort, ranges here are half open
+// `lo..hi`, the elements are moved with swaps so the reusable buffer
+// holds the merged order as indices rather than the elements themselves
+
+// runs shorter than this are extended with binary insertion sort
+const MIN_MERGE: usize = 64;
+// wins in a row before a merge first switches to galloping, the threshold
+// then drops while galloping pays off and rises each time it stops
+const MIN_GALLOP: usize = 7;
+
+pub fn tim_sort<T: Ord>(xs: &mut [T]) {
+    tim_sort_by(xs, |a, b| a.cmp(b))
+}
+
+pub fn tim_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(xs: &mut [T], mut f: F) {
+    tim_sort_by(xs, |a, b| f(a).cmp(&f(b)))
+}
+
+pub fn tim_sort_by<T, F: FnMut(&T, &T) -> Ordering>(xs: &mut [T], compare: F) {
+    tim_sort_probed(xs, compare, &mut NoProbe)
+}
+
+pub fn tim_sort_probed<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    xs: &mut [T],
+    compare: F,
+    probe: &mut P,
+) {
+    let mut s = Probed::new(xs, compare, probe);
+    let n = s.len();
+    if n < 2 {
+        return;
+    }
+    let min_run = min_run_length(n);
+    // (start, len) of the runs waiting to be merged
+    let mut runs: Vec<(usize, usize)> = vec![];
+    // allocated on the first merge, presorted input never needs it
+    let mut order: Vec<usize> = vec![];
+    // carried from one merge to the next
+    let mut min_gallop = MIN_GALLOP;
+
+    let mut lo = 0;
+    while lo < n {
+        let mut hi = next_run(&mut s, lo);
+        if hi - lo < min_run {
+            let forced = cmp::min(lo + min_run, n);
+            binary_insertion_sort(&mut s, lo, hi, forced);
+            hi = forced;
+        }
+        runs.push((lo, hi - lo));
+        lo = hi;
+
+        while let Some(r) = collapse(&runs, n) {
+            if order.capacity() == 0 {
+                order.reserve_exact(n);
+                s.alloc(order.capacity() * mem::size_of::<usize>());
+            }
+            let (start, len) = runs[r];
+            let (mid, right_len) = runs.remove(r + 1);
+            runs[r] = (start, len + right_len);
+            merge(
+                &mut s,
+                start,
+                mid,
+                mid + right_len,
+                &mut order,
+                &mut min_gallop,
+            );
+        }
+    }
+}
+
+// `n` itself below `MIN_MERGE`, otherwise a length in `MIN_MERGE / 2..=MIN_MERGE`
+// that splits `n` into a power of two runs or slightly fewer
+fn min_run_length(mut n: usize) -> usize {
+    let mut r = 0;
+    while n >= MIN_MERGE {
+        r |= n & 1;
+        n >>= 1;
+    }
+    n + r
+}
+
+// end of the run starting at `lo`, a strictly descending run is reversed
+// in place, equal keys never count as descending so stability holds
+fn next_run<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+) -> usize {
+    let n = s.len();
+    let mut hi = lo + 1;
+    if hi == n {
+        return hi;
+    }
+    if s.less(hi, lo) {
+        hi += 1;
+        while hi < n && s.less(hi, hi - 1) {
+            hi += 1;
+        }
+        for k in 0..(hi - lo) / 2 {
+            s.swap(lo + k, hi - 1 - k);
+        }
+    } else {
+        hi += 1;
+        while hi < n && !s.less(hi, hi - 1) {
+            hi += 1;
+        }
+    }
+    hi
+}
+
+// `lo..start` is sorted, inserts every element of `start..hi` after the
+// last element not greater than it
+fn binary_insertion_sort<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    start: usize,
+    hi: usize,
+) {
+    for i in start..hi {
+        let (mut l, mut r) = (lo, i);
+        while l < r {
+            let m = l + (r - l) / 2;
+            if s.less(i, m) {
+                r = m;
+            } else {
+                l = m + 1;
+            }
+        }
+        for k in (l..i).rev() {
+            s.swap(k, k + 1);
+        }
+    }
+}
+
+// the run to merge with its right neighbour, if any; keeps every run
+// longer than the two to its right together and longer than the next,
+// the last run reaching `n` merges everything
+fn collapse(runs: &[(usize, usize)], n: usize) -> Option<usize> {
+    let k = runs.len();
+    if k < 2 {
+        return None;
+    }
+    let len = |i: usize| runs[i].1;
+    let (start, last) = runs[k - 1];
+    if start + last == n
+        || len(k - 2) <= last
+        || (k >= 3 && len(k - 3) <= len(k - 2) + last)
+        || (k >= 4 && len(k - 4) <= len(k - 3) + len(k - 2))
+    {
+        if k >= 3 && len(k - 3) < last {
+            Some(k - 3)
+        } else {
+            Some(k - 2)
+        }
+    } else {
+        None
+    }
+}
+
+// merges the runs `lo..mid` and `mid..hi`, the part of the left run not
+// greater than the right's first element and the part of the right run
+// not less than the left's last are already in place and are skipped
+fn merge<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    lo: usize,
+    mid: usize,
+    hi: usize,
+    order: &mut Vec<usize>,
+    min_gallop: &mut usize,
+) {
+    s.probe().merge(lo, mid - 1, hi - 1);
+    if !s.less(mid, mid - 1) {
+        return;
+    }
+    let lo = gallop(s, mid, lo, mid, true);
+    let hi = gallop(s, mid - 1, mid, hi, false);
+
+    // decide the whole order first, as merge sort does, so a panicking
+    // comparator leaves the slice untouched
+    order.clear();
+    let (mut i, mut j) = (lo, mid);
+    let (mut left_wins, mut right_wins) = (0, 0);
+    let mut galloping = false;
+    while i < mid && j < hi {
+        if galloping || left_wins >= *min_gallop || right_wins >= *min_gallop {
+            // take whole stretches from each side until that stops paying off
+            if !galloping {
+                galloping = true;
+                *min_gallop += 1;
+            }
+            if *min_gallop > 1 {
+                *min_gallop -= 1;
+            }
+            let k = gallop(s, j, i, mid, true);
+            order.extend(i - lo..k - lo);
+            let from_left = k - i;
+            i = k;
+            if i == mid {
+                break;
+            }
+            let k = gallop(s, i, j, hi, false);
+            order.extend(j - lo..k - lo);
+            let from_right = k - j;
+            j = k;
+            if from_left < MIN_GALLOP && from_right < MIN_GALLOP {
+                galloping = false;
+                *min_gallop += 1;
+                left_wins = 0;
+                right_wins = 0;
+            }
+        } else if s.less(j, i) {
+            order.push(j - lo);
+            j += 1;
+            right_wins += 1;
+            left_wins = 0;
+        } else {
+            order.push(i - lo);
+            i += 1;
+            left_wins += 1;
+            right_wins = 0;
+        }
+    }
+    order.extend(i - lo..mid - lo);
+    order.extend(j - lo..hi - lo);
+
+    permute(s, lo, order);
+}
+
+// first position in the sorted range `lo..hi` that goes after `key`, with
+// `after_equal` the equal elements come first, otherwise they come after,
+// probes 1, 2, 4, ... places in and binary searches the last step
+fn gallop<T, F: FnMut(&T, &T) -> Ordering, P: Probe + ?Sized>(
+    s: &mut Probed<T, F, P>,
+    key: usize,
+    lo: usize,
+    hi: usize,
+    after_equal: bool,
+) -> usize {
+    let before = |s: &mut Probed<T, F, P>, q: usize| {
+        if after_equal {
+            !s.less(key, q)
+        } else {
+            s.less(q, key)
+        }
+    };
+    let mut l = lo;
+    let mut ofs = 1;
+    while lo + ofs - 1 < hi && before(s, lo + ofs - 1) {
+        l = lo + ofs;
+        ofs *= 2;
+    }
+    let mut r = cmp::min(lo + ofs - 1, hi);
+    while l < r {
+        let m = l + (r - l) / 2;
+        if before(s, m) {
+            l = m + 1;
+        } else {
+            r = m;
+        }
+    }
+    l
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::sorting::probe::SortStats;
+
+    fn stats(xs: &mut [usize]) -> SortStats {
+        let mut stats = SortStats::new();
+        tim_sort_probed(xs, |a, b| a.cmp(b), &mut stats);
+        assert!(xs.windows(2).all(|w| w[0] <= w[1]));
+        stats
+    }
+
+    #[test]
+    fn test_stable() {
+        // (key, original position), long enough to need several merges
+        let n = if cfg!(miri) { 300 } else { 5000 };
+        let mut xs: Vec<(usize, usize)> = (0..n).map(|i| (i * 7919 % 13, i)).collect();
+        tim_sort_by_key(&mut xs, |x| x.0);
+        let mut expected = xs.clone();
+        expected.sort_by_key(|x| x.0);
+        assert_eq!(xs, expected);
+        assert!(xs.windows(2).all(|w| w[0].0 < w[1].0 || w[0].1 < w[1].1));
+    }
+
+    #[test]
+    fn test_presorted_is_linear() {
+        let n = if cfg!(miri) { 300 } else { 100_000 };
+        let mut sorted: Vec<usize> = (0..n).collect();
+        let sorted = stats(&mut sorted);
+        assert_eq!(sorted.comparisons, n - 1);
+        assert_eq!(sorted.alloc_bytes, 0);
+
+        let mut reversed: Vec<usize> = (0..n).rev().collect();
+        let reversed = stats(&mut reversed);
+        assert_eq!(reversed.comparisons, n - 1);
+        assert_eq!(reversed.swaps, n / 2);
+    }
+
+    #[test]
+    fn test_few_runs() {
+        // four sorted runs, one of them descending
+        let n = if cfg!(miri) { 400 } else { 40_000 };
+        let q = n / 4;
+        let mut xs: Vec<usize> = (0..q)
+            .map(|i| 4 * i)
+            .chain((0..q).map(|i| 4 * i + 1).rev())
+            .chain((0..q).map(|i| 4 * i + 2))
+            .chain((0..q).map(|i| 4 * i + 3))
+            .collect();
+        let stats = stats(&mut xs);
+        assert_eq!(xs, (0..n).collect::<Vec<_>>());
+        // finding the runs plus one linear merge per level
+        assert!(
+            stats.comparisons < 4 * n,
+            "{} comparisons",
+            stats.comparisons
+        );
+        assert_eq!(stats.alloc_bytes, n * mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_galloping() {
+        // two runs made of long interleaved blocks
+        let n = if cfg!(miri) { 800 } else { 80_000 };
+        let block = 200;
+        let (mut left, mut right) = (vec![], vec![]);
+        for (b, start) in (0..n).step_by(block).enumerate() {
+            let side = if b % 2 == 0 { &mut left } else { &mut right };
+            side.extend(start..start + block);
+        }
+        let mut xs: Vec<usize> = left.into_iter().chain(right).collect();
+        let stats = stats(&mut xs);
+        assert_eq!(xs, (0..n).collect::<Vec<_>>());
+        // n - 1 to find the two runs, a plain merge would need about n more
+        assert!(
+            stats.comparisons < n + n / 8,
+            "{} comparisons",
+            stats.comparisons
+        );
+    }
+
+    fn min_gallop_after(left: Vec<usize>, right: Vec<usize>) -> usize {
+        let mid = left.len();
+        let mut xs: Vec<usize> = left.into_iter().chain(right).collect();
+        let hi = xs.len();
+        let mut expected = xs.clone();
+        expected.sort();
+        let mut probe = NoProbe;
+        let mut s = Probed::new(&mut xs, |a: &usize, b: &usize| a.cmp(b), &mut probe);
+        let mut min_gallop = MIN_GALLOP;
+        merge(&mut s, 0, mid, hi, &mut vec![], &mut min_gallop);
+        assert_eq!(xs, expected);
+        min_gallop
+    }
+
+    #[test]
+    fn test_min_gallop_adapts() {
+        // long stretches from each side, galloping keeps paying off
+        let block = |b: usize| b * 50..(b + 1) * 50;
+        let left = [0, 2, 4].iter().flat_map(|&b| block(b)).collect();
+        let right = [1, 3, 5].iter().flat_map(|&b| block(b)).collect();
+        assert!(min_gallop_after(left, right) < MIN_GALLOP);
+
+        // seven wins in a row start galloping, then the sides alternate
+        let left = (1..=7).chain((9..50).step_by(2)).collect();
+        let right = Some(0).into_iter().chain((8..47).step_by(2)).collect();
+        assert!(min_gallop_after(left, right) > MIN_GALLOP);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let n = if cfg!(miri) { 500 } else { 100_000 };
+        let mut rng = XorShift::default();
+        let patterns: Vec<Vec<usize>> = vec![
+            (0..n).map(|_| rng.next_u64() as usize).collect(),
+            (0..n).map(|_| rng.below(8)).collect(),
+            (0..n / 2).chain((0..n / 2).rev()).collect(),
+            (0..n / 2).rev().chain(0..n / 2).collect(),
+            (0..n).map(|i| i % 100).collect(),
+            vec![1; n],
+        ];
+        for xs in patterns {
+            let mut expected = xs.clone();
+            expected.sort();
+            let mut ys = xs;
+            tim_sort(&mut ys);
+            assert_eq!(ys, expected);
+        }
+    }
+
+    #[test]
+    fn test_collapse_keeps_invariants() {
+        let n = 1 << 20;
+        let mut runs = Vec::new();
+        let mut lo = 0;
+        let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+        while lo < n {
+            let len = cmp::min(32 + rng.below(2000), n - lo);
+            runs.push((lo, len));
+            lo += len;
+            while let Some(r) = collapse(&runs, n) {
+                let (mid, right_len) = runs.remove(r + 1);
+                assert_eq!(runs[r].0 + runs[r].1, mid);
+                runs[r].1 += right_len;
+            }
+            let k = runs.len();
+            for i in 2..k {
+                assert!(runs[i - 2].1 > runs[i - 1].1 + runs[i].1);
+            }
+            if k >= 2 {
+                assert!(runs[k - 2].1 > runs[k - 1].1);
+            }
+        }
+        assert_eq!(runs, [(0, n)]);
+    }
+}
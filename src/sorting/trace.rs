@@ -0,0 +1,342 @@
+use crate::sorting::probe::Probe;
+use crate::sorting::sorter::Sorter;
+use std::fmt::Write;
+
+// step by step record of a sort, the renderers replay it on the input
+// values, writes carry no value so only swaps move the bars
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Write(usize),
+    Partition(usize, usize),
+    Merge(usize, usize, usize),
+    Gap(usize),
+}
+
+pub trait Sink {
+    fn event(&mut self, event: Event);
+}
+
+impl Sink for Vec<Event> {
+    fn event(&mut self, event: Event) {
+        self.push(event);
+    }
+}
+
+// probe that forwards every step to a sink
+pub struct Tracer<S> {
+    sink: S,
+}
+
+impl<S: Sink> Tracer<S> {
+    pub fn new(sink: S) -> Tracer<S> {
+        Tracer { sink }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.sink
+    }
+}
+
+impl<S: Sink> Probe for Tracer<S> {
+    fn compare(&mut self, i: usize, j: usize) {
+        self.sink.event(Event::Compare(i, j));
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.sink.event(Event::Swap(i, j));
+    }
+
+    fn write(&mut self, i: usize) {
+        self.sink.event(Event::Write(i));
+    }
+
+    fn partition(&mut self, lo: usize, hi: usize) {
+        self.sink.event(Event::Partition(lo, hi));
+    }
+
+    fn merge(&mut self, lo: usize, mid: usize, hi: usize) {
+        self.sink.event(Event::Merge(lo, mid, hi));
+    }
+
+    fn gap(&mut self, h: usize) {
+        self.sink.event(Event::Gap(h));
+    }
+}
+
+pub fn trace<T>(sorter: &dyn Sorter<T>, xs: &mut [T]) -> Vec<Event> {
+    let mut tracer = Tracer::new(vec![]);
+    sorter.sort_probed(xs, &mut tracer);
+    tracer.into_inner()
+}
+
+const FRAME_HEIGHT: usize = 8;
+
+// one text frame per event: a title, the bars and the active range,
+// `?` marks compared bars and `*` moved ones
+pub fn render_frames(initial: &[usize], events: &[Event]) -> Vec<String> {
+    let mut values = initial.to_vec();
+    let max = initial.iter().copied().max().unwrap_or(0).max(1);
+    let mut range = None;
+    let mut frames = vec![];
+
+    for &event in events {
+        let mut marks = vec!['|'; values.len()];
+        let title = match event {
+            Event::Compare(i, j) => {
+                marks[i] = '?';
+                marks[j] = '?';
+                format!("compare {} {}", i, j)
+            }
+            Event::Swap(i, j) => {
+                values.swap(i, j);
+                marks[i] = '*';
+                marks[j] = '*';
+                format!("swap {} {}", i, j)
+            }
+            Event::Write(i) => {
+                marks[i] = '*';
+                format!("write {}", i)
+            }
+            Event::Partition(lo, hi) => {
+                range = Some((lo, hi));
+                format!("partition {}..={}", lo, hi)
+            }
+            Event::Merge(lo, mid, hi) => {
+                range = Some((lo, hi));
+                format!("merge {}..={} {}..={}", lo, mid, mid + 1, hi)
+            }
+            Event::Gap(h) => {
+                range = None;
+                format!("gap {}", h)
+            }
+        };
+
+        let mut frame = title;
+        for row in (1..=FRAME_HEIGHT).rev() {
+            let mut line = String::new();
+            for (&v, &mark) in values.iter().zip(&marks) {
+                line.push(if bar_height(v, max) >= row { mark } else { ' ' });
+            }
+            frame.push('\n');
+            frame.push_str(line.trim_end());
+        }
+        if let Some((lo, hi)) = range {
+            frame.push('\n');
+            frame.push_str(&" ".repeat(lo));
+            frame.push('[');
+            if hi > lo {
+                frame.push_str(&"-".repeat(hi - lo - 1));
+                frame.push(']');
+            }
+        }
+        frames.push(frame);
+    }
+    frames
+}
+
+fn bar_height(v: usize, max: usize) -> usize {
+    (v * FRAME_HEIGHT).div_ceil(max)
+}
+
+const CELL: usize = 8;
+
+// a timeline: one row of cells per state, coloured by value, with the
+// partition and merge ranges outlined on the row they start from
+pub fn render_svg(initial: &[usize], events: &[Event]) -> String {
+    let mut values = initial.to_vec();
+    let max = initial.iter().copied().max().unwrap_or(0).max(1);
+    let mut rows = vec![values.clone()];
+    let mut outlines = vec![];
+    let mut written = vec![];
+
+    for &event in events {
+        match event {
+            Event::Swap(i, j) => {
+                values.swap(i, j);
+                rows.push(values.clone());
+            }
+            Event::Write(i) => {
+                rows.push(values.clone());
+                written.push((rows.len() - 1, i));
+            }
+            Event::Partition(lo, hi) | Event::Merge(lo, _, hi) => {
+                outlines.push((rows.len() - 1, lo, hi));
+            }
+            Event::Compare(..) | Event::Gap(_) => {}
+        }
+    }
+
+    let mut svg = String::new();
+    let width = initial.len() * CELL;
+    let height = rows.len() * CELL;
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    )
+    .unwrap();
+    for (r, row) in rows.iter().enumerate() {
+        for (i, &v) in row.iter().enumerate() {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="hsl({}, 70%, 55%)"/>"#,
+                i * CELL,
+                r * CELL,
+                CELL,
+                CELL,
+                240 - v.min(max) * 240 / max
+            )
+            .unwrap();
+        }
+    }
+    for &(r, lo, hi) in &outlines {
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+            lo * CELL,
+            r * CELL,
+            (hi - lo + 1) * CELL,
+            CELL
+        )
+        .unwrap();
+    }
+    for &(r, i) in &written {
+        writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="white"/>"#,
+            i * CELL,
+            r * CELL,
+            CELL,
+            CELL
+        )
+        .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sorting::quick_sort::quick_sort_probed;
+    use crate::sorting::shell_sort::shell_sort_probed;
+    use crate::sorting::sorter::{sorters, MergeSort};
+
+    fn replay(initial: &[usize], events: &[Event]) -> Vec<usize> {
+        let mut xs = initial.to_vec();
+        for event in events {
+            if let Event::Swap(i, j) = *event {
+                xs.swap(i, j);
+            }
+        }
+        xs
+    }
+
+    #[test]
+    fn test_swaps_replay_the_sort() {
+        let initial = vec![5, 3, 9, 1, 7, 2, 8, 0, 6, 4, 3];
+        for sorter in sorters() {
+            let mut xs = initial.clone();
+            let events = trace(sorter.as_ref(), &mut xs);
+            if events.iter().any(|e| matches!(e, Event::Write(_))) {
+                continue;
+            }
+            assert_eq!(replay(&initial, &events), xs, "{}", sorter.name());
+        }
+    }
+
+    #[test]
+    fn test_quick_sort_partitions() {
+        let mut xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let mut tracer = Tracer::new(vec![]);
+        quick_sort_probed(&mut xs, |a: &i32, b: &i32| a.cmp(b), &mut tracer);
+        let events = tracer.into_inner();
+        assert_eq!(events[0], Event::Partition(0, 7));
+        // every compare and swap stays inside the innermost active partition
+        let mut range = (0, 7);
+        for &event in &events {
+            match event {
+                Event::Partition(lo, hi) => range = (lo, hi),
+                Event::Compare(i, j) | Event::Swap(i, j) => {
+                    assert!(range.0 <= i && i <= range.1);
+                    assert!(range.0 <= j && j <= range.1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_shell_sort_gaps() {
+        let mut xs: Vec<usize> = (0..50).rev().collect();
+        let mut tracer = Tracer::new(vec![]);
+        shell_sort_probed(&mut xs, |a: &usize, b: &usize| a.cmp(b), &mut tracer);
+        let gaps: Vec<usize> = tracer
+            .into_inner()
+            .into_iter()
+            .filter_map(|e| match e {
+                Event::Gap(h) => Some(h),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(gaps, [40, 13, 4, 1]);
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let mut xs = vec![4, 3, 2, 1];
+        let events = trace(&MergeSort, &mut xs);
+        let merges: Vec<Event> = events
+            .into_iter()
+            .filter(|e| matches!(e, Event::Merge(..)))
+            .collect();
+        assert_eq!(
+            merges,
+            [
+                Event::Merge(0, 0, 1),
+                Event::Merge(2, 2, 3),
+                Event::Merge(0, 1, 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let events = [
+            Event::Partition(0, 2),
+            Event::Compare(1, 0),
+            Event::Swap(0, 2),
+        ];
+        let frames = render_frames(&[8, 4, 0], &events);
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].starts_with("partition 0..=2\n"));
+        assert!(frames[0].ends_with("\n[-]"));
+        // after the swap the tall bar sits on the right
+        let last: Vec<&str> = frames[2].lines().collect();
+        assert_eq!(last[0], "swap 0 2");
+        assert_eq!(last[1], "  *");
+        assert_eq!(last[FRAME_HEIGHT], " |*");
+        let compare: Vec<&str> = frames[1].lines().collect();
+        assert_eq!(compare[FRAME_HEIGHT], "??");
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let mut xs = vec![3, 0, 2, 1];
+        let initial = xs.clone();
+        let events = trace(&MergeSort, &mut xs);
+        let swaps = events
+            .iter()
+            .filter(|e| matches!(e, Event::Swap(..)))
+            .count();
+        let svg = render_svg(&initial, &events);
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // a filled cell per value per row, one outline per merge
+        assert_eq!(svg.matches("hsl(").count(), (swaps + 1) * 4);
+        assert_eq!(svg.matches("stroke=\"black\"").count(), 3);
+    }
+}
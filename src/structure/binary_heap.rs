@@ -0,0 +1,227 @@
+use crate::structure::dary_heap::DaryHeap;
+use std::cmp::Ordering;
+
+pub use crate::structure::dary_heap::{DrainSorted, PeekMut};
+
+// the heap keeps the greatest element, as decided by `compare`, on top
+pub trait Compare<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Max;
+
+#[derive(Default, Clone, Copy)]
+pub struct Min;
+
+#[derive(Clone, Copy)]
+pub struct MaxBy<F>(pub F);
+
+#[derive(Clone, Copy)]
+pub struct MaxByKey<F>(pub F);
+
+impl<T: Ord> Compare<T> for Max {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<T: Ord> Compare<T> for Min {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Compare<T> for MaxBy<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a, b)
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> Compare<T> for MaxByKey<F> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        (self.0)(a).cmp(&(self.0)(b))
+    }
+}
+
+// the `D = 2` case of the d-ary heap
+pub type BinaryHeap<T, C = Max> = DaryHeap<T, 2, C>;
+
+// array-backed heap order, shared by the heaps built on slots
+pub(crate) trait HeapOrder {
+    // number of children per node
+    const ARITY: usize = 2;
+
+    fn size(&self) -> usize;
+
+    fn greater(&self, i: usize, j: usize) -> bool;
+
+    fn exchange(&mut self, i: usize, j: usize);
+
+    fn sink(&mut self, i: usize) {
+        self.sink_to(i, self.size());
+    }
+
+    // sink element within slots[..end]
+    fn sink_to(&mut self, i: usize, end: usize) {
+        let mut i = i;
+        while i < end {
+            let first = Self::ARITY * i + 1;
+            let mut largest = i;
+            for child in first..end.min(first + Self::ARITY) {
+                if self.greater(child, largest) {
+                    largest = child;
+                }
+            }
+            if largest != i {
+                self.exchange(i, largest);
+                i = largest;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn swim(&mut self, i: usize) {
+        let mut i = i;
+        while i != 0 && self.greater(i, (i - 1) / Self::ARITY) {
+            let parent = (i - 1) / Self::ARITY;
+            self.exchange(i, parent);
+            i = parent;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain<T, C: Compare<T>>(mut heap: BinaryHeap<T, C>) -> Vec<T> {
+        let mut xs = vec![];
+        while let Some(x) = heap.pop() {
+            xs.push(x);
+        }
+        xs
+    }
+
+    #[test]
+    fn test_max_min() {
+        let xs = vec![4, 5, 1, 2, 4];
+        let max: BinaryHeap<i32> = BinaryHeap::new().init(xs.clone());
+        assert_eq!(Some(&5), max.peek());
+        assert_eq!(drain(max), [5, 4, 4, 2, 1]);
+
+        let mut min: BinaryHeap<i32, Min> = BinaryHeap::new();
+        for x in xs {
+            min.push(x);
+        }
+        assert_eq!(Some(&1), min.peek());
+        assert_eq!(drain(min), [1, 2, 4, 4, 5]);
+    }
+
+    #[test]
+    fn test_by() {
+        let heap = BinaryHeap::by(|a: &i32, b: &i32| (a % 3).cmp(&(b % 3)).then(b.cmp(a)));
+        assert_eq!(drain(heap.init(vec![1, 2, 3, 4, 5, 6])), [2, 5, 1, 4, 3, 6]);
+    }
+
+    #[test]
+    fn test_by_key() {
+        let mut heap = BinaryHeap::by_key(|job: &(&str, u32)| job.1);
+        heap.push(("b", 2));
+        heap.push(("c", 3));
+        heap.push(("a", 1));
+        assert_eq!(heap.len(), 3);
+        assert_eq!(heap.peek(), Some(&("c", 3)));
+        assert_eq!(drain(heap), [("c", 3), ("b", 2), ("a", 1)]);
+    }
+
+    #[test]
+    fn test_peek_mut() {
+        let mut heap: BinaryHeap<i32> = vec![4, 5, 1, 2].into();
+        *heap.peek_mut().unwrap() = 0;
+        assert_eq!(heap.peek(), Some(&4));
+        {
+            let mut top = heap.peek_mut().unwrap();
+            *top += 10;
+        }
+        assert_eq!(heap.peek(), Some(&14));
+        assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 14);
+        assert_eq!(drain(heap), [2, 1, 0]);
+        assert!(BinaryHeap::<i32>::new().peek_mut().is_none());
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let heap: BinaryHeap<i32, Min> = vec![3, 1, 4, 1, 5, 9, 2, 6].into_iter().collect();
+        let mut xs = heap.clone().into_vec();
+        xs.sort();
+        assert_eq!(xs, [1, 1, 2, 3, 4, 5, 6, 9]);
+        assert_eq!(heap.into_sorted_vec(), [9, 6, 5, 4, 3, 2, 1, 1]);
+
+        let heap: BinaryHeap<i32> = (0..10).rev().collect();
+        assert_eq!(heap.into_sorted_vec(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut heap: BinaryHeap<i32> = (0..5).collect();
+        assert_eq!(heap.drain().count(), 5);
+        assert!(heap.is_empty());
+
+        heap.extend(0..5);
+        assert_eq!(heap.drain_sorted().take(2).collect::<Vec<_>>(), [4, 3]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_append_extend() {
+        let mut a: BinaryHeap<i32> = vec![1, 8, 3].into();
+        let mut b: BinaryHeap<i32> = (4..=7).collect();
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(a.len(), 7);
+
+        a.extend(vec![2, 9]);
+        a.extend(Some(0));
+        assert_eq!(a.into_sorted_vec(), (0..=9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_append_other_comparator() {
+        let key: fn(&i32) -> i32 = |&x| x;
+        let neg: fn(&i32) -> i32 = |&x| -x;
+        let mut a = BinaryHeap::by_key(key);
+        a.push(1);
+        let mut b = BinaryHeap::by_key(neg);
+        b.extend(vec![5, 3, 9, 7]);
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(drain(a), [9, 7, 5, 3, 1]);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut heap: BinaryHeap<i32> = BinaryHeap::with_capacity(10);
+        assert!(heap.capacity() >= 10);
+        heap.reserve(20);
+        heap.extend(0..10);
+        heap.retain(|x| x % 2 == 0);
+        assert_eq!(heap.iter().count(), 5);
+        assert_eq!(drain(heap), [8, 6, 4, 2, 0]);
+    }
+
+    #[test]
+    fn test_debug() {
+        let heap: BinaryHeap<i32> = vec![1].into();
+        assert_eq!(format!("{:?}", heap), "[1]");
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut heap: BinaryHeap<i32> = BinaryHeap::new();
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+    }
+}
@@ -0,0 +1,197 @@
+use crate::structure::binary_heap::{Compare, Max};
+use crate::structure::priority_queue::{MeldableHeap, PriorityQueue};
+use std::cmp::Ordering;
+use std::mem;
+
+// forest of binomial trees, `trees[k]` holds the tree of order k if any
+pub struct BinomialHeap<T, C = Max> {
+    trees: Vec<Option<Box<Node<T>>>>,
+    len: usize,
+    cmp: C,
+}
+
+struct Node<T> {
+    elem: T,
+    // `children[i]` is a tree of order i
+    children: Vec<Box<Node<T>>>,
+}
+
+// joins two trees of the same order into one of the next order
+fn link<T, C: Compare<T>>(cmp: &C, mut a: Box<Node<T>>, mut b: Box<Node<T>>) -> Box<Node<T>> {
+    if cmp.compare(&b.elem, &a.elem) == Ordering::Greater {
+        mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+impl<T, C: Compare<T> + Default> BinomialHeap<T, C> {
+    pub fn new() -> BinomialHeap<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for BinomialHeap<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> BinomialHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> BinomialHeap<T, C> {
+        BinomialHeap {
+            trees: vec![],
+            len: 0,
+            cmp,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.top().map(|k| &self.trees[k].as_ref().unwrap().elem)
+    }
+
+    pub fn push(&mut self, x: T) {
+        let node = Box::new(Node {
+            elem: x,
+            children: vec![],
+        });
+        self.meld_trees(vec![Some(node)]);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let k = self.top()?;
+        let root = self.trees[k].take().unwrap();
+        while let Some(None) = self.trees.last() {
+            self.trees.pop();
+        }
+        self.meld_trees(root.children.into_iter().map(Some).collect());
+        self.len -= 1;
+        Some(root.elem)
+    }
+
+    pub fn meld(&mut self, mut other: BinomialHeap<T, C>) {
+        self.meld_trees(mem::take(&mut other.trees));
+        self.len += mem::replace(&mut other.len, 0);
+    }
+
+    // order of the tree whose root is the top
+    fn top(&self) -> Option<usize> {
+        let mut best: Option<(usize, &T)> = None;
+        for (k, tree) in self.trees.iter().enumerate() {
+            if let Some(node) = tree {
+                match best {
+                    Some((_, elem)) if self.cmp.compare(&node.elem, elem) != Ordering::Greater => {}
+                    _ => best = Some((k, &node.elem)),
+                }
+            }
+        }
+        best.map(|(k, _)| k)
+    }
+
+    // adds the forests like binary numbers
+    fn meld_trees(&mut self, other: Vec<Option<Box<Node<T>>>>) {
+        if self.trees.len() < other.len() {
+            self.trees.resize_with(other.len(), || None);
+        }
+        let mut other = other.into_iter();
+        let mut carry: Option<Box<Node<T>>> = None;
+        for k in 0..self.trees.len() {
+            let mut same_order: Vec<Box<Node<T>>> =
+                vec![self.trees[k].take(), other.next().flatten(), carry.take()]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+            if same_order.len() >= 2 {
+                let b = same_order.pop().unwrap();
+                let a = same_order.pop().unwrap();
+                carry = Some(link(&self.cmp, a, b));
+            }
+            self.trees[k] = same_order.pop();
+        }
+        if carry.is_some() {
+            self.trees.push(carry);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for BinomialHeap<T, C> {
+    fn push(&mut self, x: T) {
+        BinomialHeap::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        BinomialHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        BinomialHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        BinomialHeap::len(self)
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for BinomialHeap<T, C> {
+    fn meld(&mut self, other: Self) {
+        BinomialHeap::meld(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::binary_heap::Min;
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = BinomialHeap::<i32>::new();
+        for &x in &[4, 5, 1, 2, 4] {
+            heap.push(x);
+        }
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(2));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = BinomialHeap::<i32, Min>::new();
+        let mut b = BinomialHeap::<i32, Min>::new();
+        for i in 0..37 {
+            a.push(i * 2);
+        }
+        for i in 0..63 {
+            b.push(i * 2 + 1);
+        }
+        a.meld(b);
+        assert_eq!(a.len(), 100);
+        // one tree per set bit of the length
+        assert_eq!(a.trees.iter().filter(|t| t.is_some()).count(), 3);
+        let mut xs = vec![];
+        while let Some(x) = a.pop() {
+            xs.push(x);
+        }
+        let mut expected: Vec<i32> = (0..37)
+            .map(|i| i * 2)
+            .chain((0..63).map(|i| i * 2 + 1))
+            .collect();
+        expected.sort();
+        assert_eq!(xs, expected);
+    }
+}
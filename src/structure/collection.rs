@@ -0,0 +1,435 @@
+use crate::structure::deque;
+use crate::structure::unsafe_deque;
+use crate::structure::unsafe_red_black_tree::RedBlackTree;
+use crate::structure::unsafe_stack;
+use std::cell::Ref;
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Deref;
+
+pub use crate::structure::priority_queue::{MeldableHeap, PriorityQueue};
+
+// shared vocabulary for the containers, `Ref` lets a `RefCell` backed
+// container hand out a guard where the others hand out a plain reference
+
+pub trait Stack<T> {
+    type Ref<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    fn push(&mut self, x: T);
+
+    fn pop(&mut self) -> Option<T>;
+
+    fn peek(&self) -> Option<Self::Ref<'_>>;
+
+    fn is_empty(&self) -> bool;
+}
+
+pub trait Queue<T> {
+    type Ref<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    fn enqueue(&mut self, x: T);
+
+    fn dequeue(&mut self) -> Option<T>;
+
+    fn front(&self) -> Option<Self::Ref<'_>>;
+
+    fn is_empty(&self) -> bool;
+}
+
+pub trait Deque<T> {
+    type Ref<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+
+    fn push_front(&mut self, x: T);
+
+    fn push_back(&mut self, x: T);
+
+    fn pop_front(&mut self) -> Option<T>;
+
+    fn pop_back(&mut self) -> Option<T>;
+
+    fn peek_front(&self) -> Option<Self::Ref<'_>>;
+
+    fn peek_back(&self) -> Option<Self::Ref<'_>>;
+
+    fn is_empty(&self) -> bool;
+}
+
+pub trait OrderedMap<K: Ord, V> {
+    fn insert(&mut self, key: K, value: V);
+
+    fn get(&self, key: &K) -> Option<&V>;
+
+    fn min_key(&self) -> Option<&K>;
+
+    fn max_key(&self) -> Option<&K>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+impl<T> Stack<T> for unsafe_stack::Stack<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn push(&mut self, x: T) {
+        unsafe_stack::Stack::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        unsafe_stack::Stack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        unsafe_stack::Stack::peek(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        unsafe_stack::Stack::is_empty(self)
+    }
+}
+
+impl<T> Stack<T> for Vec<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn push(&mut self, x: T) {
+        Vec::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.last()
+    }
+
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+}
+
+// implements `Queue` on top of a type's `Deque` impl
+macro_rules! queue_from_deque {
+    ($($ty:ident)::+) => {
+        impl<T> Queue<T> for $($ty)::+<T> {
+            type Ref<'a> = <Self as Deque<T>>::Ref<'a> where T: 'a;
+
+            fn enqueue(&mut self, x: T) {
+                Deque::push_back(self, x)
+            }
+
+            fn dequeue(&mut self) -> Option<T> {
+                Deque::pop_front(self)
+            }
+
+            fn front(&self) -> Option<Self::Ref<'_>> {
+                Deque::peek_front(self)
+            }
+
+            fn is_empty(&self) -> bool {
+                Deque::is_empty(self)
+            }
+        }
+    };
+}
+
+queue_from_deque!(deque::Deque);
+queue_from_deque!(unsafe_deque::Deque);
+queue_from_deque!(VecDeque);
+
+impl<T> Deque<T> for deque::Deque<T> {
+    type Ref<'a>
+        = Ref<'a, T>
+    where
+        T: 'a;
+
+    fn push_front(&mut self, x: T) {
+        deque::Deque::push_front(self, x)
+    }
+
+    fn push_back(&mut self, x: T) {
+        deque::Deque::push_back(self, x)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        deque::Deque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        deque::Deque::pop_back(self)
+    }
+
+    fn peek_front(&self) -> Option<Ref<'_, T>> {
+        deque::Deque::peek_front(self)
+    }
+
+    fn peek_back(&self) -> Option<Ref<'_, T>> {
+        deque::Deque::peek_back(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        deque::Deque::is_empty(self)
+    }
+}
+
+impl<T> Deque<T> for unsafe_deque::Deque<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn push_front(&mut self, x: T) {
+        unsafe_deque::Deque::push_front(self, x)
+    }
+
+    fn push_back(&mut self, x: T) {
+        unsafe_deque::Deque::push_back(self, x)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        unsafe_deque::Deque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        unsafe_deque::Deque::pop_back(self)
+    }
+
+    fn peek_front(&self) -> Option<&T> {
+        unsafe_deque::Deque::peek_front(self)
+    }
+
+    fn peek_back(&self) -> Option<&T> {
+        unsafe_deque::Deque::peek_back(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        unsafe_deque::Deque::is_empty(self)
+    }
+}
+
+impl<T> Deque<T> for VecDeque<T> {
+    type Ref<'a>
+        = &'a T
+    where
+        T: 'a;
+
+    fn push_front(&mut self, x: T) {
+        VecDeque::push_front(self, x)
+    }
+
+    fn push_back(&mut self, x: T) {
+        VecDeque::push_back(self, x)
+    }
+
+    fn pop_front(&mut self) -> Option<T> {
+        VecDeque::pop_front(self)
+    }
+
+    fn pop_back(&mut self) -> Option<T> {
+        VecDeque::pop_back(self)
+    }
+
+    fn peek_front(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn peek_back(&self) -> Option<&T> {
+        self.back()
+    }
+
+    fn is_empty(&self) -> bool {
+        VecDeque::is_empty(self)
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> for RedBlackTree<K, V> {
+    fn insert(&mut self, key: K, value: V) {
+        self.put(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        RedBlackTree::get(self, key)
+    }
+
+    fn min_key(&self) -> Option<&K> {
+        RedBlackTree::min_key(self)
+    }
+
+    fn max_key(&self) -> Option<&K> {
+        RedBlackTree::max_key(self)
+    }
+
+    fn len(&self) -> usize {
+        RedBlackTree::len(self)
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> for BTreeMap<K, V> {
+    fn insert(&mut self, key: K, value: V) {
+        BTreeMap::insert(self, key, value);
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        BTreeMap::get(self, key)
+    }
+
+    fn min_key(&self) -> Option<&K> {
+        self.keys().next()
+    }
+
+    fn max_key(&self) -> Option<&K> {
+        self.keys().next_back()
+    }
+
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // conformance suites, every implementation of a trait runs its suite
+
+    fn check_stack<S: Stack<i32> + Default>() {
+        let mut stack = S::default();
+        assert!(stack.is_empty());
+        assert!(stack.pop().is_none());
+        assert!(stack.peek().is_none());
+        for x in 0..100 {
+            stack.push(x);
+            assert_eq!(stack.peek().as_deref(), Some(&x));
+        }
+        assert!(!stack.is_empty());
+        for x in (50..100).rev() {
+            assert_eq!(stack.pop(), Some(x));
+        }
+        stack.push(-1);
+        assert_eq!(stack.pop(), Some(-1));
+        for x in (0..50).rev() {
+            assert_eq!(stack.pop(), Some(x));
+        }
+        assert!(stack.is_empty());
+        assert!(stack.pop().is_none());
+    }
+
+    fn check_queue<Q: Queue<i32> + Default>() {
+        let mut queue = Q::default();
+        assert!(queue.is_empty());
+        assert!(queue.dequeue().is_none());
+        assert!(queue.front().is_none());
+        for x in 0..100 {
+            queue.enqueue(x);
+            assert_eq!(queue.front().as_deref(), Some(&0));
+        }
+        for x in 0..50 {
+            assert_eq!(queue.dequeue(), Some(x));
+        }
+        queue.enqueue(100);
+        for x in 50..=100 {
+            assert_eq!(queue.front().as_deref(), Some(&x));
+            assert_eq!(queue.dequeue(), Some(x));
+        }
+        assert!(queue.is_empty());
+        assert!(queue.dequeue().is_none());
+    }
+
+    fn check_deque<D: Deque<i32> + Default>() {
+        let mut deque = D::default();
+        assert!(deque.is_empty());
+        assert!(deque.pop_front().is_none());
+        assert!(deque.pop_back().is_none());
+        assert!(deque.peek_front().is_none());
+        assert!(deque.peek_back().is_none());
+
+        deque.push_back(1);
+        assert_eq!(deque.peek_front().as_deref(), Some(&1));
+        assert_eq!(deque.peek_back().as_deref(), Some(&1));
+        assert_eq!(deque.pop_front(), Some(1));
+        assert!(deque.is_empty());
+
+        deque.push_front(1);
+        assert_eq!(deque.pop_back(), Some(1));
+        assert!(deque.is_empty());
+
+        for x in 0..10 {
+            deque.push_back(x);
+            deque.push_front(-x);
+        }
+        assert_eq!(deque.peek_front().as_deref(), Some(&-9));
+        assert_eq!(deque.peek_back().as_deref(), Some(&9));
+        for x in (0..10).rev() {
+            assert_eq!(deque.pop_back(), Some(x));
+            assert_eq!(deque.pop_front(), Some(-x));
+        }
+        assert!(deque.is_empty());
+        assert!(deque.pop_back().is_none());
+    }
+
+    fn check_ordered_map<M: OrderedMap<u32, String> + Default>() {
+        let mut map = M::default();
+        assert!(map.is_empty());
+        assert_eq!(map.min_key(), None);
+        assert_eq!(map.max_key(), None);
+
+        let keys: Vec<u32> = (0..200).map(|i| i * 37 % 101).collect();
+        for &k in &keys {
+            map.insert(k, k.to_string());
+        }
+        assert_eq!(map.len(), 101);
+        assert_eq!(map.min_key(), Some(&0));
+        assert_eq!(map.max_key(), Some(&100));
+        assert!(map.contains_key(&42));
+        assert!(!map.contains_key(&101));
+
+        map.insert(42, "answer".to_string());
+        assert_eq!(map.len(), 101);
+        assert_eq!(map.get(&42).map(|v| v.as_str()), Some("answer"));
+        assert_eq!(map.get(&7).map(|v| v.as_str()), Some("7"));
+        assert_eq!(map.get(&500), None);
+    }
+
+    #[test]
+    fn test_stacks() {
+        check_stack::<unsafe_stack::Stack<i32>>();
+        check_stack::<Vec<i32>>();
+    }
+
+    #[test]
+    fn test_queues() {
+        check_queue::<deque::Deque<i32>>();
+        check_queue::<unsafe_deque::Deque<i32>>();
+        check_queue::<VecDeque<i32>>();
+    }
+
+    #[test]
+    fn test_deques() {
+        check_deque::<deque::Deque<i32>>();
+        check_deque::<unsafe_deque::Deque<i32>>();
+        check_deque::<VecDeque<i32>>();
+    }
+
+    #[test]
+    fn test_ordered_maps() {
+        check_ordered_map::<RedBlackTree<u32, String>>();
+        check_ordered_map::<BTreeMap<u32, String>>();
+    }
+}
@@ -0,0 +1,360 @@
+use crate::structure::binary_heap::{Compare, HeapOrder, Max, MaxBy, MaxByKey};
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+use std::slice;
+use std::vec;
+
+// heap with `D` children per node, a larger `D` gives a shallower tree
+// and cheaper pushes at the cost of more comparisons per pop,
+// `BinaryHeap` is the `D = 2` case
+pub struct DaryHeap<T, const D: usize, C = Max> {
+    heap: Vec<T>,
+    cmp: C,
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> Default for DaryHeap<T, D, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> DaryHeap<T, D, C> {
+    pub fn new() -> DaryHeap<T, D, C> {
+        Self::with_comparator(C::default())
+    }
+
+    pub fn with_capacity(capacity: usize) -> DaryHeap<T, D, C> {
+        let mut heap = Self::new();
+        heap.heap.reserve(capacity);
+        heap
+    }
+}
+
+impl<T, const D: usize, F: Fn(&T, &T) -> Ordering> DaryHeap<T, D, MaxBy<F>> {
+    pub fn by(f: F) -> DaryHeap<T, D, MaxBy<F>> {
+        Self::with_comparator(MaxBy(f))
+    }
+}
+
+impl<T, const D: usize, K: Ord, F: Fn(&T) -> K> DaryHeap<T, D, MaxByKey<F>> {
+    pub fn by_key(f: F) -> DaryHeap<T, D, MaxByKey<F>> {
+        Self::with_comparator(MaxByKey(f))
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> DaryHeap<T, D, C> {
+    // fails to compile, rather than panics, for a heap with fewer than two
+    // children per node
+    const VALID_ARITY: () = assert!(D >= 2, "a heap needs at least two children per node");
+
+    pub fn with_comparator(cmp: C) -> DaryHeap<T, D, C> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_ARITY;
+        DaryHeap { heap: vec![], cmp }
+    }
+
+    pub fn init(mut self, xs: Vec<T>) -> DaryHeap<T, D, C> {
+        self.heap = xs;
+        self.rebuild();
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    pub fn comparator(&self) -> &C {
+        &self.cmp
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.heap.reserve(additional);
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    // the top is sunk back into place when the guard is dropped
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, D, C>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(PeekMut {
+            heap: self,
+            sift: true,
+        })
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let top = self.heap.swap_remove(0);
+        if !self.is_empty() {
+            self.sink(0);
+        }
+
+        Some(top)
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.heap.push(x);
+        self.swim(self.len() - 1);
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    // elements in arbitrary order
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.heap.iter()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+    }
+
+    // ascending order, the top of the heap comes last
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        for end in (1..self.len()).rev() {
+            self.heap.swap(0, end);
+            self.sink_to(0, end);
+        }
+        self.heap
+    }
+
+    // removes all elements in arbitrary order
+    pub fn drain(&mut self) -> vec::Drain<'_, T> {
+        self.heap.drain(..)
+    }
+
+    // removes all elements in pop order
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, D, C> {
+        DrainSorted { heap: self }
+    }
+
+    // `other` may order its elements differently, so they are always
+    // sifted in under this heap's comparator
+    pub fn append(&mut self, other: &mut DaryHeap<T, D, C>) {
+        let start = self.len();
+        self.heap.append(&mut other.heap);
+        self.rebuild_tail(start);
+    }
+
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) {
+        self.heap.retain(f);
+        self.rebuild();
+    }
+
+    // sinks every internal node, the last one is the parent of the last slot
+    fn rebuild(&mut self) {
+        if self.len() < 2 {
+            return;
+        }
+        for i in (0..=(self.len() - 2) / D).rev() {
+            self.sink(i);
+        }
+    }
+
+    // restores the heap after elements were added from `start` on
+    fn rebuild_tail(&mut self, start: usize) {
+        let len = self.len();
+        let tail = len - start;
+        let log = (usize::BITS - len.leading_zeros()) as usize;
+        if tail * log < 2 * len {
+            for i in start..len {
+                self.swim(i);
+            }
+        } else {
+            self.rebuild();
+        }
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> HeapOrder for DaryHeap<T, D, C> {
+    const ARITY: usize = D;
+
+    fn size(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn greater(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(&self.heap[i], &self.heap[j]) == Ordering::Greater
+    }
+
+    fn exchange(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+    }
+}
+
+pub struct PeekMut<'a, T, const D: usize, C: Compare<T>> {
+    heap: &'a mut DaryHeap<T, D, C>,
+    sift: bool,
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> PeekMut<'a, T, D, C> {
+    pub fn pop(mut this: PeekMut<'a, T, D, C>) -> T {
+        this.sift = false;
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> Deref for PeekMut<'a, T, D, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.heap[0]
+    }
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> DerefMut for PeekMut<'a, T, D, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.heap.heap[0]
+    }
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> Drop for PeekMut<'a, T, D, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            self.heap.sink(0);
+        }
+    }
+}
+
+pub struct DrainSorted<'a, T, const D: usize, C: Compare<T>> {
+    heap: &'a mut DaryHeap<T, D, C>,
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> Iterator for DrainSorted<'a, T, D, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<'a, T, const D: usize, C: Compare<T>> Drop for DrainSorted<'a, T, D, C> {
+    fn drop(&mut self) {
+        self.heap.clear();
+    }
+}
+
+impl<T: Clone, const D: usize, C: Clone> Clone for DaryHeap<T, D, C> {
+    fn clone(&self) -> Self {
+        DaryHeap {
+            heap: self.heap.clone(),
+            cmp: self.cmp.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, const D: usize, C> fmt::Debug for DaryHeap<T, D, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.heap.iter()).finish()
+    }
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> From<Vec<T>> for DaryHeap<T, D, C> {
+    fn from(xs: Vec<T>) -> Self {
+        DaryHeap::new().init(xs)
+    }
+}
+
+impl<T, const D: usize, C: Compare<T> + Default> FromIterator<T> for DaryHeap<T, D, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        DaryHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T, const D: usize, C: Compare<T>> Extend<T> for DaryHeap<T, D, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let start = self.len();
+        self.heap.extend(iter);
+        self.rebuild_tail(start);
+    }
+}
+
+impl<T, const D: usize, C> IntoIterator for DaryHeap<T, D, C> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    // elements in arbitrary order
+    fn into_iter(self) -> vec::IntoIter<T> {
+        self.heap.into_iter()
+    }
+}
+
+impl<'a, T, const D: usize, C> IntoIterator for &'a DaryHeap<T, D, C> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.heap.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::binary_heap::Min;
+
+    fn drain<T, const D: usize, C: Compare<T>>(mut heap: DaryHeap<T, D, C>) -> Vec<T> {
+        let mut xs = vec![];
+        while let Some(x) = heap.pop() {
+            xs.push(x);
+        }
+        xs
+    }
+
+    fn check_arity<const D: usize>() {
+        let xs: Vec<u32> = (0..500).map(|i| i * 7919 % 503).collect();
+        let mut expected = xs.clone();
+        expected.sort();
+
+        let heap: DaryHeap<u32, D, Min> = xs.iter().cloned().collect();
+        assert_eq!(heap.len(), 500);
+        assert_eq!(drain(heap), expected);
+
+        let mut heap: DaryHeap<u32, D> = DaryHeap::with_capacity(xs.len());
+        for &x in &xs {
+            heap.push(x);
+        }
+        assert_eq!(heap.peek(), expected.last());
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn test_arities() {
+        check_arity::<2>();
+        check_arity::<3>();
+        check_arity::<4>();
+        check_arity::<8>();
+        check_arity::<16>();
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut heap: DaryHeap<i32, 4> = DaryHeap::new();
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+        assert_eq!(heap.pop(), None);
+        assert_eq!(drain(DaryHeap::<i32, 4>::new().init(vec![4])), [4]);
+    }
+}
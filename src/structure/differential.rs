@@ -0,0 +1,378 @@
+// differential tests, random operation sequences run against our structures
+// and the std collection they mimic, a failing sequence is shrunk before
+// it is reported
+//
+// DIFFERENTIAL_SEED=<n> replays a single seed
+
+use crate::random::XorShift;
+use crate::structure::collection::{Deque, OrderedMap, Stack};
+use crate::structure::deque;
+use crate::structure::max_heap::MaxHeap;
+use crate::structure::min_heap::MinHeap;
+use crate::structure::unsafe_deque;
+use crate::structure::unsafe_red_black_tree::RedBlackTree;
+use crate::structure::unsafe_stack;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, VecDeque};
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+const SEEDS: u64 = 64;
+const OPS: usize = 300;
+
+struct Failure<O> {
+    seed: u64,
+    ops: Vec<O>,
+    message: String,
+}
+
+// runs the ops, comparing after every step, a panic counts as a failure
+fn replay<O, R>(run: &R, ops: &[O]) -> Result<(), String>
+where
+    R: Fn(&[O]) -> Result<(), String>,
+{
+    match panic::catch_unwind(AssertUnwindSafe(|| run(ops))) {
+        Ok(result) => result,
+        Err(payload) => Err(payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string())),
+    }
+}
+
+// drops ever smaller chunks of the trace while it still fails
+fn shrink<O: Clone, R>(run: &R, mut ops: Vec<O>, mut message: String) -> (Vec<O>, String)
+where
+    R: Fn(&[O]) -> Result<(), String>,
+{
+    let mut chunk = ops.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        let mut shrunk = false;
+        while start < ops.len() {
+            let end = (start + chunk).min(ops.len());
+            let candidate: Vec<O> = ops[..start].iter().chain(&ops[end..]).cloned().collect();
+            match replay(run, &candidate) {
+                Err(m) => {
+                    ops = candidate;
+                    message = m;
+                    shrunk = true;
+                }
+                Ok(()) => start += chunk,
+            }
+        }
+        if !shrunk {
+            chunk /= 2;
+        }
+    }
+    (ops, message)
+}
+
+fn find_failure<O, G, R>(seeds: &[u64], len: usize, gen: G, run: R) -> Option<Failure<O>>
+where
+    O: Clone,
+    G: Fn(&mut XorShift) -> O,
+    R: Fn(&[O]) -> Result<(), String>,
+{
+    for &seed in seeds {
+        let mut rng = XorShift::new(seed);
+        let ops: Vec<O> = (0..len).map(|_| gen(&mut rng)).collect();
+        if let Err(message) = replay(&run, &ops) {
+            let (ops, message) = shrink(&run, ops, message);
+            return Some(Failure { seed, ops, message });
+        }
+    }
+    None
+}
+
+fn seeds() -> Vec<u64> {
+    match std::env::var("DIFFERENTIAL_SEED") {
+        Ok(seed) => vec![seed.parse().expect("DIFFERENTIAL_SEED must be a number")],
+        Err(_) if cfg!(miri) => (0..2).collect(),
+        Err(_) => (0..SEEDS).collect(),
+    }
+}
+
+fn check<O, G, R>(name: &str, gen: G, run: R)
+where
+    O: Clone + Debug,
+    G: Fn(&mut XorShift) -> O,
+    R: Fn(&[O]) -> Result<(), String>,
+{
+    if let Some(failure) = find_failure(&seeds(), OPS, gen, run) {
+        let trace: Vec<String> = failure
+            .ops
+            .iter()
+            .map(|op| format!("    {:?}", op))
+            .collect();
+        panic!(
+            "{} diverged from std with seed {}: {}\nminimal trace ({} ops):\n{}",
+            name,
+            failure.seed,
+            failure.message,
+            failure.ops.len(),
+            trace.join("\n")
+        );
+    }
+}
+
+fn expect_eq<T: PartialEq + Debug>(step: usize, ours: T, std: T) -> Result<(), String> {
+    if ours == std {
+        Ok(())
+    } else {
+        Err(format!("step {}: got {:?}, std gave {:?}", step, ours, std))
+    }
+}
+
+#[derive(Clone, Debug)]
+enum MapOp {
+    Insert(u8, i32),
+    Get(u8),
+    Len,
+    MinKey,
+    MaxKey,
+}
+
+fn gen_map_op(rng: &mut XorShift) -> MapOp {
+    let key = rng.below(48) as u8;
+    match rng.below(6) {
+        0 | 1 => MapOp::Insert(key, rng.below(1000) as i32),
+        2 | 3 => MapOp::Get(key),
+        4 => MapOp::Len,
+        _ if rng.below(2) == 0 => MapOp::MinKey,
+        _ => MapOp::MaxKey,
+    }
+}
+
+fn run_map<M: OrderedMap<u8, i32> + Default>(ops: &[MapOp]) -> Result<(), String> {
+    let mut ours = M::default();
+    let mut std = BTreeMap::new();
+    for (step, op) in ops.iter().enumerate() {
+        match *op {
+            MapOp::Insert(k, v) => {
+                ours.insert(k, v);
+                std.insert(k, v);
+            }
+            MapOp::Get(k) => expect_eq(step, ours.get(&k), std.get(&k))?,
+            MapOp::Len => expect_eq(step, ours.len(), std.len())?,
+            MapOp::MinKey => expect_eq(step, ours.min_key(), std.keys().next())?,
+            MapOp::MaxKey => expect_eq(step, ours.max_key(), std.keys().next_back())?,
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+enum DequeOp {
+    PushFront(i32),
+    PushBack(i32),
+    PopFront,
+    PopBack,
+    PeekFront,
+    PeekBack,
+    IsEmpty,
+}
+
+fn gen_deque_op(rng: &mut XorShift) -> DequeOp {
+    let x = rng.below(100) as i32;
+    match rng.below(7) {
+        0 => DequeOp::PushFront(x),
+        1 => DequeOp::PushBack(x),
+        2 => DequeOp::PopFront,
+        3 => DequeOp::PopBack,
+        4 => DequeOp::PeekFront,
+        5 => DequeOp::PeekBack,
+        _ => DequeOp::IsEmpty,
+    }
+}
+
+fn run_deque<D: Deque<i32> + Default>(ops: &[DequeOp]) -> Result<(), String> {
+    let mut ours = D::default();
+    let mut std = VecDeque::new();
+    for (step, op) in ops.iter().enumerate() {
+        match *op {
+            DequeOp::PushFront(x) => {
+                ours.push_front(x);
+                std.push_front(x);
+            }
+            DequeOp::PushBack(x) => {
+                ours.push_back(x);
+                std.push_back(x);
+            }
+            DequeOp::PopFront => expect_eq(step, ours.pop_front(), std.pop_front())?,
+            DequeOp::PopBack => expect_eq(step, ours.pop_back(), std.pop_back())?,
+            DequeOp::PeekFront => expect_eq(step, ours.peek_front().as_deref(), std.front())?,
+            DequeOp::PeekBack => expect_eq(step, ours.peek_back().as_deref(), std.back())?,
+            DequeOp::IsEmpty => expect_eq(step, ours.is_empty(), std.is_empty())?,
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+enum HeapOp {
+    Push(i32),
+    Pop,
+    Peek,
+    Len,
+}
+
+fn gen_heap_op(rng: &mut XorShift) -> HeapOp {
+    match rng.below(5) {
+        0 | 1 => HeapOp::Push(rng.below(50) as i32),
+        2 => HeapOp::Pop,
+        3 => HeapOp::Peek,
+        _ => HeapOp::Len,
+    }
+}
+
+fn run_max_heap(ops: &[HeapOp]) -> Result<(), String> {
+    let mut ours: MaxHeap<i32> = MaxHeap::new();
+    let mut std = BinaryHeap::new();
+    for (step, op) in ops.iter().enumerate() {
+        match *op {
+            HeapOp::Push(x) => {
+                ours.push(x);
+                std.push(x);
+            }
+            HeapOp::Pop => expect_eq(step, ours.pop_max(), std.pop())?,
+            HeapOp::Peek => expect_eq(step, ours.maxmium(), std.peek())?,
+            HeapOp::Len => expect_eq(step, ours.len(), std.len())?,
+        }
+    }
+    Ok(())
+}
+
+fn run_min_heap(ops: &[HeapOp]) -> Result<(), String> {
+    let mut ours: MinHeap<i32> = MinHeap::new();
+    let mut std = BinaryHeap::new();
+    for (step, op) in ops.iter().enumerate() {
+        match *op {
+            HeapOp::Push(x) => {
+                ours.push(x);
+                std.push(Reverse(x));
+            }
+            HeapOp::Pop => expect_eq(step, ours.pop_min(), std.pop().map(|r| r.0))?,
+            HeapOp::Peek => expect_eq(step, ours.min(), std.peek().map(|r| &r.0))?,
+            HeapOp::Len => expect_eq(step, ours.len(), std.len())?,
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+enum StackOp {
+    Push(i32),
+    Pop,
+    Peek,
+    IsEmpty,
+}
+
+fn gen_stack_op(rng: &mut XorShift) -> StackOp {
+    match rng.below(4) {
+        0 => StackOp::Push(rng.below(100) as i32),
+        1 => StackOp::Pop,
+        2 => StackOp::Peek,
+        _ => StackOp::IsEmpty,
+    }
+}
+
+fn run_stack<S: Stack<i32> + Default>(ops: &[StackOp]) -> Result<(), String> {
+    let mut ours = S::default();
+    let mut std = Vec::new();
+    for (step, op) in ops.iter().enumerate() {
+        match *op {
+            StackOp::Push(x) => {
+                ours.push(x);
+                std.push(x);
+            }
+            StackOp::Pop => expect_eq(step, ours.pop(), std.pop())?,
+            StackOp::Peek => expect_eq(step, ours.peek().as_deref(), std.last())?,
+            StackOp::IsEmpty => expect_eq(step, ours.is_empty(), std.is_empty())?,
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_red_black_tree_vs_btree_map() {
+    check("RedBlackTree", gen_map_op, run_map::<RedBlackTree<u8, i32>>);
+}
+
+#[test]
+fn test_deques_vs_vec_deque() {
+    check("Deque", gen_deque_op, run_deque::<deque::Deque<i32>>);
+    check(
+        "unsafe Deque",
+        gen_deque_op,
+        run_deque::<unsafe_deque::Deque<i32>>,
+    );
+}
+
+#[test]
+fn test_heaps_vs_binary_heap() {
+    check("MaxHeap", gen_heap_op, run_max_heap);
+    check("MinHeap", gen_heap_op, run_min_heap);
+}
+
+#[test]
+fn test_stack_vs_vec() {
+    check("Stack", gen_stack_op, run_stack::<unsafe_stack::Stack<i32>>);
+}
+
+// a stack that loses every element pushed onto a stack of three
+#[derive(Default)]
+struct LossyStack(Vec<i32>);
+
+impl Stack<i32> for LossyStack {
+    type Ref<'a> = &'a i32;
+
+    fn push(&mut self, x: i32) {
+        if self.0.len() < 3 {
+            self.0.push(x);
+        }
+    }
+
+    fn pop(&mut self) -> Option<i32> {
+        self.0.pop()
+    }
+
+    fn peek(&self) -> Option<&i32> {
+        self.0.last()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+#[test]
+fn test_shrinks_to_minimal_trace() {
+    let failure = find_failure(&[7], 500, gen_stack_op, run_stack::<LossyStack>).unwrap();
+    assert_eq!(failure.seed, 7);
+    // four pushes and one observation are needed to notice the loss
+    assert_eq!(failure.ops.len(), 5, "{:?}", failure.ops);
+    assert_eq!(
+        failure
+            .ops
+            .iter()
+            .filter(|op| matches!(op, StackOp::Push(_)))
+            .count(),
+        4
+    );
+    assert!(failure.message.starts_with("step 4"), "{}", failure.message);
+}
+
+#[test]
+fn test_panics_are_failures() {
+    let failure = find_failure(&[1, 2, 3], 50, gen_heap_op, |ops: &[HeapOp]| {
+        if ops.iter().any(|op| matches!(op, HeapOp::Pop)) {
+            panic!("pop is broken");
+        }
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(failure.ops.len(), 1);
+    assert_eq!(failure.message, "pop is broken");
+}
@@ -0,0 +1,63 @@
+// element type for leak and double free tests, every `Counted` records its
+// id when dropped so a test can check each value was dropped exactly once
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+pub(crate) struct DropCounter {
+    dropped: Rc<RefCell<Vec<usize>>>,
+}
+
+pub(crate) struct Counted {
+    pub(crate) id: usize,
+    counter: DropCounter,
+}
+
+impl DropCounter {
+    pub(crate) fn new() -> DropCounter {
+        DropCounter::default()
+    }
+
+    pub(crate) fn value(&self, id: usize) -> Counted {
+        Counted {
+            id,
+            counter: self.clone(),
+        }
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.dropped.borrow().len()
+    }
+
+    pub(crate) fn dropped(&self, id: usize) -> bool {
+        self.dropped.borrow().contains(&id)
+    }
+
+    // panics unless every id in 0..n was dropped exactly once
+    pub(crate) fn assert_all_dropped_once(&self, n: usize) {
+        let mut ids = self.dropped.borrow().clone();
+        ids.sort_unstable();
+        let expected: Vec<usize> = (0..n).collect();
+        assert_eq!(ids, expected, "values leaked or dropped twice");
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        self.counter.dropped.borrow_mut().push(self.id);
+    }
+}
+
+impl PartialEq for Counted {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl fmt::Debug for Counted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Counted({})", self.id)
+    }
+}
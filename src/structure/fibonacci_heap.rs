@@ -0,0 +1,563 @@
+use crate::structure::binary_heap::{Compare, Min};
+use crate::structure::priority_queue::{MeldableHeap, PriorityQueue};
+use std::cmp::Ordering;
+
+// nodes live in an arena and link to each other by index, root and child
+// lists are circular doubly linked lists as in CLRS chapter 19; each heap
+// owns its arena, so `union` splices the root lists in O(1) but first has to
+// copy the other heap's nodes over in O(|other|)
+pub struct FibonacciHeap<T, C = Min> {
+    slots: Vec<Slot<T>>,
+    free: Vec<usize>,
+    top: Option<usize>,
+    len: usize,
+    cmp: C,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Handle {
+    index: usize,
+    generation: u64,
+}
+
+// translates handles of a heap that was merged by `union`
+#[derive(Clone, Copy, Debug)]
+pub struct HandleMap {
+    offset: usize,
+}
+
+struct Slot<T> {
+    generation: u64,
+    node: Option<Node<T>>,
+}
+
+struct Node<T> {
+    key: T,
+    parent: Option<usize>,
+    child: Option<usize>,
+    left: usize,
+    right: usize,
+    degree: usize,
+    mark: bool,
+}
+
+impl HandleMap {
+    pub fn handle(&self, handle: Handle) -> Handle {
+        Handle {
+            index: handle.index + self.offset,
+            generation: handle.generation,
+        }
+    }
+}
+
+impl<T, C: Compare<T> + Default> FibonacciHeap<T, C> {
+    pub fn new() -> FibonacciHeap<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for FibonacciHeap<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> FibonacciHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> FibonacciHeap<T, C> {
+        FibonacciHeap {
+            slots: vec![],
+            free: vec![],
+            top: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.slots
+            .get(handle.index)
+            .is_some_and(|slot| slot.generation == handle.generation && slot.node.is_some())
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        Some(&self.node(handle.index).key)
+    }
+
+    pub fn min(&self) -> Option<&T> {
+        self.top.map(|x| &self.node(x).key)
+    }
+
+    pub fn insert(&mut self, key: T) -> Handle {
+        let node = Node {
+            key,
+            parent: None,
+            child: None,
+            left: 0,
+            right: 0,
+            degree: 0,
+            mark: false,
+        };
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index].node = Some(node);
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    node: Some(node),
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.make_single(index);
+        self.add_root(index);
+        self.len += 1;
+        Handle {
+            index,
+            generation: self.slots[index].generation,
+        }
+    }
+
+    pub fn extract_min(&mut self) -> Option<T> {
+        let z = self.top?;
+
+        // children of z join the root list
+        if let Some(child) = self.node(z).child {
+            let mut x = child;
+            loop {
+                self.node_mut(x).parent = None;
+                x = self.node(x).right;
+                if x == child {
+                    break;
+                }
+            }
+            self.concat_lists(z, child);
+        }
+
+        let next = self.node(z).right;
+        self.unlink(z);
+        if next == z {
+            self.top = None;
+        } else {
+            self.top = Some(next);
+            self.consolidate();
+        }
+
+        self.len -= 1;
+        Some(self.release(z))
+    }
+
+    // handles into `other` stay valid once translated by the returned map,
+    // its nodes are copied over in O(|other|), the lists are spliced in O(1)
+    pub fn union(&mut self, other: FibonacciHeap<T, C>) -> HandleMap {
+        let offset = self.slots.len();
+        let other_top = other.top.map(|x| x + offset);
+        self.len += other.len;
+        self.free.extend(other.free.iter().map(|&i| i + offset));
+        self.slots.extend(other.slots.into_iter().map(|mut slot| {
+            if let Some(node) = &mut slot.node {
+                node.parent = node.parent.map(|i| i + offset);
+                node.child = node.child.map(|i| i + offset);
+                node.left += offset;
+                node.right += offset;
+            }
+            slot
+        }));
+
+        if let Some(b) = other_top {
+            match self.top {
+                None => self.top = Some(b),
+                Some(a) => {
+                    self.concat_lists(a, b);
+                    if self.greater(b, a) {
+                        self.top = Some(b);
+                    }
+                }
+            }
+        }
+        HandleMap { offset }
+    }
+
+    // moves the key towards the top, panics if the new key is worse than the old one
+    pub fn decrease_key(&mut self, handle: Handle, key: T) {
+        assert!(self.contains(handle), "handle is not in the heap");
+        let x = handle.index;
+        assert!(
+            self.cmp.compare(&key, &self.node(x).key) != Ordering::Less,
+            "new key is worse than the current key"
+        );
+        self.node_mut(x).key = key;
+
+        if let Some(y) = self.node(x).parent {
+            if self.greater(x, y) {
+                self.cut(x, y);
+                self.cascading_cut(y);
+            }
+        }
+        if self.greater(x, self.top.unwrap()) {
+            self.top = Some(x);
+        }
+    }
+
+    pub fn delete(&mut self, handle: Handle) -> Option<T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        let x = handle.index;
+        if let Some(y) = self.node(x).parent {
+            self.cut(x, y);
+            self.cascading_cut(y);
+        }
+        self.top = Some(x);
+        self.extract_min()
+    }
+
+    fn node(&self, i: usize) -> &Node<T> {
+        self.slots[i].node.as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, i: usize) -> &mut Node<T> {
+        self.slots[i].node.as_mut().unwrap()
+    }
+
+    fn greater(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(&self.node(i).key, &self.node(j).key) == Ordering::Greater
+    }
+
+    fn release(&mut self, i: usize) -> T {
+        let slot = &mut self.slots[i];
+        slot.generation += 1;
+        self.free.push(i);
+        slot.node.take().unwrap().key
+    }
+
+    fn make_single(&mut self, x: usize) {
+        let node = self.node_mut(x);
+        node.left = x;
+        node.right = x;
+    }
+
+    // removes x from its circular list, leaving it a list of its own
+    fn unlink(&mut self, x: usize) {
+        let (left, right) = (self.node(x).left, self.node(x).right);
+        self.node_mut(left).right = right;
+        self.node_mut(right).left = left;
+        self.make_single(x);
+    }
+
+    // joins the circular lists containing a and b
+    fn concat_lists(&mut self, a: usize, b: usize) {
+        let a_right = self.node(a).right;
+        let b_left = self.node(b).left;
+        self.node_mut(a).right = b;
+        self.node_mut(b).left = a;
+        self.node_mut(a_right).left = b_left;
+        self.node_mut(b_left).right = a_right;
+    }
+
+    fn add_root(&mut self, x: usize) {
+        match self.top {
+            None => self.top = Some(x),
+            Some(top) => {
+                self.concat_lists(top, x);
+                if self.greater(x, top) {
+                    self.top = Some(x);
+                }
+            }
+        }
+    }
+
+    // makes y a child of x
+    fn link(&mut self, y: usize, x: usize) {
+        self.make_single(y);
+        match self.node(x).child {
+            None => self.node_mut(x).child = Some(y),
+            Some(child) => self.concat_lists(child, y),
+        }
+        let node = self.node_mut(y);
+        node.parent = Some(x);
+        node.mark = false;
+        self.node_mut(x).degree += 1;
+    }
+
+    // links roots of equal degree until all root degrees differ
+    fn consolidate(&mut self) {
+        let start = self.top.unwrap();
+        let mut roots = vec![start];
+        let mut w = self.node(start).right;
+        while w != start {
+            roots.push(w);
+            w = self.node(w).right;
+        }
+
+        let mut by_degree: Vec<Option<usize>> = vec![];
+        for mut x in roots {
+            let mut d = self.node(x).degree;
+            loop {
+                if d >= by_degree.len() {
+                    by_degree.resize(d + 1, None);
+                }
+                let y = match by_degree[d].take() {
+                    Some(y) => y,
+                    None => break,
+                };
+                let (mut top, mut other) = (x, y);
+                if self.greater(y, x) {
+                    top = y;
+                    other = x;
+                }
+                self.link(other, top);
+                x = top;
+                d += 1;
+            }
+            by_degree[d] = Some(x);
+        }
+
+        self.top = None;
+        for x in by_degree.into_iter().flatten() {
+            self.make_single(x);
+            self.add_root(x);
+        }
+    }
+
+    fn cut(&mut self, x: usize, y: usize) {
+        if self.node(y).child == Some(x) {
+            let right = self.node(x).right;
+            self.node_mut(y).child = if right == x { None } else { Some(right) };
+        }
+        self.unlink(x);
+        self.node_mut(y).degree -= 1;
+        let node = self.node_mut(x);
+        node.parent = None;
+        node.mark = false;
+        let top = self.top.unwrap();
+        self.concat_lists(top, x);
+    }
+
+    fn cascading_cut(&mut self, y: usize) {
+        let mut y = y;
+        while let Some(z) = self.node(y).parent {
+            if !self.node(y).mark {
+                self.node_mut(y).mark = true;
+                return;
+            }
+            self.cut(y, z);
+            y = z;
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for FibonacciHeap<T, C> {
+    fn push(&mut self, x: T) {
+        self.insert(x);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.extract_min()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.min()
+    }
+
+    fn len(&self) -> usize {
+        FibonacciHeap::len(self)
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for FibonacciHeap<T, C> {
+    fn meld(&mut self, other: Self) {
+        self.union(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::structure::binary_heap::Max;
+
+    // subtree sizes, checking size(x) >= F(degree(x) + 2) on the way
+    fn check_subtree<T, C: Compare<T>>(
+        heap: &FibonacciHeap<T, C>,
+        x: usize,
+        fib: &[usize],
+    ) -> usize {
+        let node = heap.node(x);
+        let mut size = 1;
+        let mut degree = 0;
+        if let Some(child) = node.child {
+            let mut c = child;
+            loop {
+                assert_eq!(heap.node(c).parent, Some(x));
+                assert!(!heap.greater(c, x));
+                size += check_subtree(heap, c, fib);
+                degree += 1;
+                c = heap.node(c).right;
+                if c == child {
+                    break;
+                }
+            }
+        }
+        assert_eq!(degree, node.degree);
+        assert!(size >= fib[degree + 2]);
+        size
+    }
+
+    fn check_invariants<T, C: Compare<T>>(heap: &FibonacciHeap<T, C>) {
+        let mut fib: Vec<usize> = vec![0, 1];
+        while fib.len() < 96 {
+            let n = fib.len();
+            fib.push(fib[n - 1].saturating_add(fib[n - 2]));
+        }
+        let mut total = 0;
+        if let Some(top) = heap.top {
+            let mut x = top;
+            loop {
+                assert!(heap.node(x).parent.is_none());
+                assert!(!heap.greater(x, top));
+                total += check_subtree(heap, x, &fib);
+                x = heap.node(x).right;
+                if x == top {
+                    break;
+                }
+            }
+        }
+        assert_eq!(total, heap.len());
+    }
+
+    #[test]
+    fn test_insert_extract() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        for &x in &[4, 5, 1, 2, 4] {
+            heap.insert(x);
+        }
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.min(), Some(&1));
+        assert_eq!(heap.extract_min(), Some(1));
+        assert_eq!(heap.extract_min(), Some(2));
+        assert_eq!(heap.extract_min(), Some(4));
+        assert_eq!(heap.extract_min(), Some(4));
+        assert_eq!(heap.extract_min(), Some(5));
+        assert_eq!(heap.extract_min(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_decrease_key_delete() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let handles: Vec<Handle> = (0..20).map(|i| heap.insert(i * 10)).collect();
+        assert_eq!(heap.extract_min(), Some(0));
+        assert!(!heap.contains(handles[0]));
+
+        heap.decrease_key(handles[15], 5);
+        assert_eq!(heap.min(), Some(&5));
+        assert_eq!(heap.get(handles[15]), Some(&5));
+        assert_eq!(heap.delete(handles[15]), Some(5));
+        assert_eq!(heap.delete(handles[15]), None);
+        assert_eq!(heap.delete(handles[7]), Some(70));
+        check_invariants(&heap);
+
+        let mut xs = vec![];
+        while let Some(x) = heap.extract_min() {
+            xs.push(x);
+        }
+        let expected: Vec<i32> = (1..20)
+            .filter(|&i| i != 7 && i != 15)
+            .map(|i| i * 10)
+            .collect();
+        assert_eq!(xs, expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decrease_key_panics_on_worse_key() {
+        let mut heap = FibonacciHeap::<i32>::new();
+        let handle = heap.insert(1);
+        heap.decrease_key(handle, 2);
+    }
+
+    #[test]
+    fn test_union() {
+        let mut a = FibonacciHeap::<i32, Max>::new();
+        let mut b = FibonacciHeap::<i32, Max>::new();
+        for i in 0..10 {
+            a.insert(i * 2);
+        }
+        let hb: Vec<Handle> = (0..10).map(|i| b.insert(i * 2 + 1)).collect();
+        b.extract_min();
+        let map = a.union(b);
+        assert_eq!(a.len(), 19);
+        a.decrease_key(map.handle(hb[0]), 100);
+        assert_eq!(a.min(), Some(&100));
+        assert!(!a.contains(map.handle(hb[9])));
+        check_invariants(&a);
+
+        let mut xs = vec![];
+        while let Some(x) = a.extract_min() {
+            xs.push(x);
+        }
+        let expected: Vec<i32> = Some(100)
+            .into_iter()
+            .chain((0..=18).rev().filter(|&x| x != 1))
+            .collect();
+        assert_eq!(xs, expected);
+    }
+
+    #[test]
+    fn test_random_workload() {
+        let mut rng = XorShift::new(0x9e37_79b9_7f4a_7c15);
+        let mut heap = FibonacciHeap::<u64>::new();
+        let mut live: Vec<(Handle, u64)> = vec![];
+
+        let rounds = if cfg!(miri) { 1_000 } else { 60_000 };
+        for round in 0..rounds {
+            match rng.next_u64() % 8 {
+                0..=3 => {
+                    let key = rng.next_u64() % 1_000_000;
+                    live.push((heap.insert(key), key));
+                }
+                4 | 5 if !live.is_empty() => {
+                    let i = (rng.next_u64() as usize) % live.len();
+                    let (handle, key) = live[i];
+                    let new_key = key - key.min(rng.next_u64() % 1000);
+                    heap.decrease_key(handle, new_key);
+                    live[i].1 = new_key;
+                }
+                6 if !live.is_empty() => {
+                    let i = (rng.next_u64() as usize) % live.len();
+                    let (handle, key) = live.swap_remove(i);
+                    assert_eq!(heap.delete(handle), Some(key));
+                }
+                _ => {
+                    let expected = live.iter().map(|&(_, key)| key).min();
+                    assert_eq!(heap.extract_min(), expected);
+                    if let Some(key) = expected {
+                        let i = live
+                            .iter()
+                            .position(|&(h, k)| k == key && !heap.contains(h))
+                            .unwrap();
+                        live.swap_remove(i);
+                    }
+                }
+            }
+            assert_eq!(heap.len(), live.len());
+            if round % 5000 == 0 {
+                check_invariants(&heap);
+            }
+        }
+        check_invariants(&heap);
+    }
+}
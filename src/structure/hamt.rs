@@ -0,0 +1,555 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+use std::mem;
+use std::slice;
+use std::sync::Arc;
+
+const BITS: u32 = 5;
+const MASK: u64 = (1 << BITS) - 1;
+
+pub struct HamtMap<K, V, S = RandomState> {
+    root: Arc<Node<K, V>>,
+    len: usize,
+    hash_builder: S,
+}
+
+// batch-mutable view, nodes it owns alone are updated in place
+pub struct TransientHamtMap<K, V, S = RandomState> {
+    map: HamtMap<K, V, S>,
+}
+
+#[derive(Clone)]
+enum Node<K, V> {
+    Branch {
+        bitmap: u32,
+        entries: Vec<Entry<K, V>>,
+    },
+    // keys whose full hashes are equal
+    Collision {
+        hash: u64,
+        pairs: Vec<(K, V)>,
+    },
+}
+
+#[derive(Clone)]
+enum Entry<K, V> {
+    Leaf(u64, K, V),
+    Node(Arc<Node<K, V>>),
+}
+
+fn fragment(hash: u64, shift: u32) -> u32 {
+    ((hash >> shift) & MASK) as u32
+}
+
+fn bit_index(bitmap: u32, bit: u32) -> usize {
+    (bitmap & (bit - 1)).count_ones() as usize
+}
+
+impl<K, V> Node<K, V> {
+    fn empty() -> Node<K, V> {
+        Node::Branch {
+            bitmap: 0,
+            entries: vec![],
+        }
+    }
+
+    fn get<Q>(&self, hash: u64, shift: u32, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        match self {
+            Node::Branch { bitmap, entries } => {
+                let bit = 1 << fragment(hash, shift);
+                if bitmap & bit == 0 {
+                    return None;
+                }
+                match &entries[bit_index(*bitmap, bit)] {
+                    Entry::Leaf(h, k, v) if *h == hash && k.borrow() == key => Some(v),
+                    Entry::Leaf(..) => None,
+                    Entry::Node(child) => child.get(hash, shift + BITS, key),
+                }
+            }
+            Node::Collision { pairs, .. } => pairs
+                .iter()
+                .find(|(k, _)| k.borrow() == key)
+                .map(|(_, v)| v),
+        }
+    }
+
+    // smallest subtree holding two leaves with different keys
+    fn pair(shift: u32, a: (u64, K, V), b: (u64, K, V)) -> Node<K, V> {
+        if a.0 == b.0 {
+            return Node::Collision {
+                hash: a.0,
+                pairs: vec![(a.1, a.2), (b.1, b.2)],
+            };
+        }
+        let (fa, fb) = (fragment(a.0, shift), fragment(b.0, shift));
+        if fa == fb {
+            let child = Node::pair(shift + BITS, a, b);
+            return Node::Branch {
+                bitmap: 1 << fa,
+                entries: vec![Entry::Node(Arc::new(child))],
+            };
+        }
+        let (first, second) = if fa < fb { (a, b) } else { (b, a) };
+        Node::Branch {
+            bitmap: (1 << fa) | (1 << fb),
+            entries: vec![
+                Entry::Leaf(first.0, first.1, first.2),
+                Entry::Leaf(second.0, second.1, second.2),
+            ],
+        }
+    }
+
+    // the single leaf left in a node that should be pulled up into its parent
+    fn lone_leaf(&self) -> bool {
+        match self {
+            Node::Branch { entries, .. } => {
+                entries.len() == 1 && matches!(entries[0], Entry::Leaf(..))
+            }
+            Node::Collision { pairs, .. } => pairs.len() == 1,
+        }
+    }
+}
+
+impl<K: Eq + Clone, V: Clone> Node<K, V> {
+    fn insert(node: &mut Arc<Node<K, V>>, hash: u64, shift: u32, key: K, value: V) -> Option<V> {
+        if let Node::Collision { hash: h, .. } = **node {
+            if h != hash {
+                let collision = mem::replace(node, Arc::new(Node::empty()));
+                *node = Arc::new(Node::Branch {
+                    bitmap: 1 << fragment(h, shift),
+                    entries: vec![Entry::Node(collision)],
+                });
+            }
+        }
+
+        match Arc::make_mut(node) {
+            Node::Branch { bitmap, entries } => {
+                let bit = 1 << fragment(hash, shift);
+                let idx = bit_index(*bitmap, bit);
+                if *bitmap & bit == 0 {
+                    *bitmap |= bit;
+                    entries.insert(idx, Entry::Leaf(hash, key, value));
+                    return None;
+                }
+                match &mut entries[idx] {
+                    Entry::Node(child) => Node::insert(child, hash, shift + BITS, key, value),
+                    Entry::Leaf(h, k, v) if *h == hash && *k == key => Some(mem::replace(v, value)),
+                    Entry::Leaf(..) => {
+                        if let Entry::Leaf(h, k, v) = entries.remove(idx) {
+                            let child = Node::pair(shift + BITS, (h, k, v), (hash, key, value));
+                            entries.insert(idx, Entry::Node(Arc::new(child)));
+                        }
+                        None
+                    }
+                }
+            }
+            Node::Collision { pairs, .. } => match pairs.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, v)) => Some(mem::replace(v, value)),
+                None => {
+                    pairs.push((key, value));
+                    None
+                }
+            },
+        }
+    }
+
+    // callers make sure the key is present, so no node is copied in vain
+    fn remove<Q>(node: &mut Arc<Node<K, V>>, hash: u64, shift: u32, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        match Arc::make_mut(node) {
+            Node::Branch { bitmap, entries } => {
+                let bit = 1 << fragment(hash, shift);
+                if *bitmap & bit == 0 {
+                    return None;
+                }
+                let idx = bit_index(*bitmap, bit);
+                match &mut entries[idx] {
+                    Entry::Leaf(h, k, _) if *h == hash && (*k).borrow() == key => {
+                        *bitmap &= !bit;
+                        match entries.remove(idx) {
+                            Entry::Leaf(_, _, v) => Some(v),
+                            Entry::Node(_) => unreachable!(),
+                        }
+                    }
+                    Entry::Leaf(..) => None,
+                    Entry::Node(child) => {
+                        let removed = Node::remove(child, hash, shift + BITS, key);
+                        if child.lone_leaf() {
+                            entries[idx] = match Arc::make_mut(child) {
+                                Node::Branch { entries, .. } => entries.pop().unwrap(),
+                                Node::Collision { hash, pairs } => {
+                                    let (k, v) = pairs.pop().unwrap();
+                                    Entry::Leaf(*hash, k, v)
+                                }
+                            };
+                        }
+                        removed
+                    }
+                }
+            }
+            Node::Collision { pairs, .. } => {
+                let idx = pairs.iter().position(|(k, _)| k.borrow() == key)?;
+                Some(pairs.swap_remove(idx).1)
+            }
+        }
+    }
+}
+
+impl<K, V> HamtMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V, S> HamtMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HamtMap {
+            root: Arc::new(Node::empty()),
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let entries = match &*self.root {
+            Node::Branch { entries, .. } => entries.iter(),
+            Node::Collision { .. } => [].iter(),
+        };
+        Iter {
+            stack: vec![entries],
+            pairs: [].iter(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> HamtMap<K, V, S> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.root.get(self.hash_builder.hash_one(key), 0, key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+}
+
+impl<K, V, S> HamtMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let mut transient = self.transient();
+        transient.insert(key, value);
+        transient.persistent()
+    }
+
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let mut transient = self.transient();
+        transient.remove(key);
+        transient.persistent()
+    }
+
+    pub fn transient(&self) -> TransientHamtMap<K, V, S> {
+        TransientHamtMap { map: self.clone() }
+    }
+}
+
+impl<K, V, S> TransientHamtMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    pub fn len(&self) -> usize {
+        self.map.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.len == 0
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self.map.hash_builder.hash_one(&key);
+        let old = Node::insert(&mut self.map.root, hash, 0, key, value);
+        if old.is_none() {
+            self.map.len += 1;
+        }
+        old
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.map.contains_key(key) {
+            return None;
+        }
+        let hash = self.map.hash_builder.hash_one(key);
+        let old = Node::remove(&mut self.map.root, hash, 0, key);
+        self.map.len -= 1;
+        old
+    }
+
+    pub fn persistent(self) -> HamtMap<K, V, S> {
+        self.map
+    }
+}
+
+impl<K, V, S: Default> Default for HamtMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S: Clone> Clone for HamtMap<K, V, S> {
+    fn clone(&self) -> Self {
+        HamtMap {
+            root: self.root.clone(),
+            len: self.len,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<K, V, S> PartialEq for HamtMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq, S: BuildHasher> Eq for HamtMap<K, V, S> {}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for HamtMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for HamtMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut transient = HamtMap::default().transient();
+        for (key, value) in iter {
+            transient.insert(key, value);
+        }
+        transient.persistent()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<slice::Iter<'a, Entry<K, V>>>,
+    pairs: slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, v)) = self.pairs.next() {
+                return Some((k, v));
+            }
+            let entry = loop {
+                match self.stack.last_mut()?.next() {
+                    Some(entry) => break entry,
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+            };
+            match entry {
+                Entry::Leaf(_, k, v) => return Some((k, v)),
+                Entry::Node(node) => match &**node {
+                    Node::Branch { entries, .. } => self.stack.push(entries.iter()),
+                    Node::Collision { pairs, .. } => self.pairs = pairs.iter(),
+                },
+            }
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HamtMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use std::collections::HashMap;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    // keeps only two low bits and two high bits, forcing deep paths and collisions
+    #[derive(Default)]
+    struct PoorHasher(u64);
+
+    impl Hasher for PoorHasher {
+        fn finish(&self) -> u64 {
+            (self.0 & 0b11) | ((self.0 & 0b1100) << 60)
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            for &b in bytes {
+                self.0 = self.0.wrapping_mul(31).wrapping_add(b as u64);
+            }
+        }
+    }
+
+    type PoorMap<K, V> = HamtMap<K, V, BuildHasherDefault<PoorHasher>>;
+
+    #[test]
+    fn test_insert_get() {
+        let map = HamtMap::new();
+        assert!(map.is_empty());
+        let map = map.insert("a", 1).insert("b", 2).insert("c", 3);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(map.get("b"), Some(&2));
+        assert_eq!(map.get("c"), Some(&3));
+        assert_eq!(map.get("d"), None);
+
+        let map = map.insert("a", 10);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get("a"), Some(&10));
+    }
+
+    #[test]
+    fn test_persistence() {
+        let v1: HamtMap<i32, i32> = (0..100).map(|i| (i, i)).collect();
+        let v2 = v1.insert(1000, 0).remove(&5);
+        assert_eq!(v1.len(), 100);
+        assert_eq!(v2.len(), 100);
+        assert!(v1.contains_key(&5));
+        assert!(!v2.contains_key(&5));
+        assert!(!v1.contains_key(&1000));
+        assert_eq!(v1.remove(&7777), v1);
+    }
+
+    #[test]
+    fn test_collisions() {
+        let mut expected = HashMap::new();
+        let mut map = PoorMap::default();
+        for i in 0..300 {
+            map = map.insert(i, i * 2);
+            expected.insert(i, i * 2);
+        }
+        for i in (0..300).step_by(3) {
+            map = map.remove(&i);
+            expected.remove(&i);
+        }
+        assert_eq!(map.len(), expected.len());
+        for i in 0..300 {
+            assert_eq!(map.get(&i), expected.get(&i));
+        }
+        for i in 0..300 {
+            map = map.remove(&i);
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_iter() {
+        let map: PoorMap<i32, i32> = (0..50).map(|i| (i, -i)).collect();
+        let mut pairs: Vec<(i32, i32)> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        pairs.sort();
+        assert_eq!(pairs, (0..50).map(|i| (i, -i)).collect::<Vec<_>>());
+        assert_eq!(format!("{:?}", HamtMap::new().insert(1, 2)), "{1: 2}");
+    }
+
+    #[test]
+    fn test_transient() {
+        let base: HamtMap<u32, u32> = (0..10).map(|i| (i, i)).collect();
+        let mut transient = base.transient();
+        for i in 0..5000 {
+            assert_eq!(
+                transient.insert(i, i + 1),
+                if i < 10 { Some(i) } else { None }
+            );
+        }
+        assert_eq!(transient.remove(&0), Some(1));
+        assert_eq!(transient.remove(&0), None);
+        let map = transient.persistent();
+
+        assert_eq!(map.len(), 4999);
+        assert_eq!(map.get(&4999), Some(&5000));
+        assert_eq!(base.len(), 10);
+        assert_eq!(base.get(&3), Some(&3));
+    }
+
+    #[test]
+    fn test_random_against_std() {
+        let mut expected = HashMap::new();
+        let mut transient = PoorMap::default().transient();
+        let mut rng = XorShift::new(42);
+        let rounds = if cfg!(miri) { 500 } else { 20_000 };
+        for _ in 0..rounds {
+            let x = rng.next_u64();
+            let key = (x >> 33) % 2000;
+            if x & 1 == 0 {
+                assert_eq!(transient.insert(key, x), expected.insert(key, x));
+            } else {
+                assert_eq!(transient.remove(&key), expected.remove(&key));
+            }
+        }
+        let map = transient.persistent();
+        assert_eq!(map.len(), expected.len());
+        for (k, v) in map.iter() {
+            assert_eq!(expected.get(k), Some(v));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::structure::binary_heap::{BinaryHeap, Compare, MaxByKey, PeekMut};
+use crate::structure::min_heap::MinHeap;
+use std::cmp::Ordering;
+
+// the k greatest elements in descending order, holding at most k in memory
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(iter: I, k: usize) -> Vec<T> {
+    let mut heap = MinHeap::with_capacity(k);
+    keep_best(&mut heap, iter, k);
+    heap.into_sorted_vec()
+}
+
+// the k elements with the smallest keys in ascending key order
+pub fn k_smallest_by_key<T, K, I, F>(iter: I, k: usize, f: F) -> Vec<T>
+where
+    K: Ord,
+    I: IntoIterator<Item = T>,
+    F: Fn(&T) -> K,
+{
+    let mut heap = BinaryHeap::with_comparator(MaxByKey(f));
+    heap.reserve(k);
+    keep_best(&mut heap, iter, k);
+    heap.into_sorted_vec()
+}
+
+// the heap's top is the worst element kept so far and the first to be replaced
+fn keep_best<T, C: Compare<T>, I: IntoIterator<Item = T>>(
+    heap: &mut BinaryHeap<T, C>,
+    iter: I,
+    k: usize,
+) {
+    if k == 0 {
+        return;
+    }
+    for x in iter {
+        if heap.len() < k {
+            heap.push(x);
+            continue;
+        }
+        if heap.comparator().compare(&x, heap.peek().unwrap()) == Ordering::Less {
+            *heap.peek_mut().unwrap() = x;
+        }
+    }
+}
+
+pub fn kmerge<I>(iters: I) -> KMerge<<I::Item as IntoIterator>::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::Item: Ord,
+{
+    let mut iters: Vec<_> = iters.into_iter().map(|it| it.into_iter()).collect();
+    let mut heap = MinHeap::with_capacity(iters.len());
+    for (source, it) in iters.iter_mut().enumerate() {
+        if let Some(item) = it.next() {
+            heap.push(Head { item, source });
+        }
+    }
+    KMerge { iters, heap }
+}
+
+// merges sorted iterators lazily, equal items come out in the order of their sources
+pub struct KMerge<I: Iterator>
+where
+    I::Item: Ord,
+{
+    iters: Vec<I>,
+    heap: MinHeap<Head<I::Item>>,
+}
+
+struct Head<T> {
+    item: T,
+    source: usize,
+}
+
+impl<T: Ord> Ord for Head<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.item
+            .cmp(&other.item)
+            .then(self.source.cmp(&other.source))
+    }
+}
+
+impl<T: Ord> PartialOrd for Head<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Head<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Head<T> {}
+
+impl<I: Iterator> Iterator for KMerge<I>
+where
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let mut top = self.heap.peek_mut()?;
+        match self.iters[top.source].next() {
+            Some(item) => Some(std::mem::replace(&mut top.item, item)),
+            None => Some(PeekMut::pop(top).item),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lower = self.heap.len();
+        let mut upper = Some(lower);
+        for (l, h) in self.iters.iter().map(|it| it.size_hint()) {
+            lower = lower.saturating_add(l);
+            upper = match (upper, h) {
+                (Some(a), Some(b)) => a.checked_add(b),
+                _ => None,
+            };
+        }
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k() {
+        let xs = vec![5, 1, 9, 3, 7, 9, 2, 8];
+        assert_eq!(top_k(xs.clone(), 3), [9, 9, 8]);
+        assert_eq!(top_k(xs.clone(), 0), Vec::<i32>::new());
+        assert_eq!(top_k(xs, 20), [9, 9, 8, 7, 5, 3, 2, 1]);
+        let n = if cfg!(miri) { 1_000 } else { 1_000_000 };
+        assert_eq!(top_k(0..n, 2), [n - 1, n - 2]);
+    }
+
+    #[test]
+    fn test_k_smallest_by_key() {
+        let words = ["pear", "fig", "banana", "kiwi", "apple", "plum"];
+        let shortest = k_smallest_by_key(words.iter().cloned(), 2, |w| w.len());
+        assert_eq!(shortest[0], "fig");
+        assert_eq!(shortest[1].len(), 4);
+
+        let by_distance = k_smallest_by_key(-5..=5, 3, |x: &i32| (x - 2).abs());
+        assert_eq!(by_distance[0], 2);
+        assert_eq!(by_distance.len(), 3);
+        assert!(by_distance[1..].contains(&1) && by_distance[1..].contains(&3));
+    }
+
+    #[test]
+    fn test_kmerge() {
+        let merged: Vec<i32> =
+            kmerge(vec![vec![1, 4, 7], vec![], vec![2, 5, 8, 9], vec![3, 6]]).collect();
+        assert_eq!(merged, (1..=9).collect::<Vec<_>>());
+
+        let empty: Vec<Vec<i32>> = vec![];
+        assert_eq!(kmerge(empty).next(), None);
+
+        let it = kmerge(vec![0..3, 1..4]);
+        assert_eq!(it.size_hint(), (6, Some(6)));
+        assert_eq!(it.collect::<Vec<_>>(), [0, 1, 1, 2, 2, 3]);
+    }
+
+    // orders by the number only so ties can be told apart by their tag
+    #[derive(Debug)]
+    struct Tagged(i32, char);
+
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Tagged {}
+
+    #[test]
+    fn test_kmerge_is_lazy_and_stable() {
+        // unbounded sources only yield what is asked for
+        let evens = (0..).step_by(2).map(|x| Tagged(x, 'a'));
+        let odds = (1..).step_by(2).map(|x| Tagged(x, 'b'));
+        let ties = vec![Tagged(2, 'c'), Tagged(4, 'c')].into_iter();
+        let sources: Vec<Box<dyn Iterator<Item = Tagged>>> =
+            vec![Box::new(ties), Box::new(evens), Box::new(odds)];
+        let tags: String = kmerge(sources).take(8).map(|t| t.1).collect();
+        assert_eq!(tags, "abcabcab");
+    }
+}
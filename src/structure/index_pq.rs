@@ -0,0 +1,293 @@
+use crate::structure::binary_heap::{Compare, HeapOrder, Max, Min};
+use std::cmp::Ordering;
+
+// priority queue of integer handles, each handle owns one key that can be changed in place
+pub struct IndexPQ<T, C = Min> {
+    pq: Vec<usize>,
+    qp: Vec<Option<usize>>,
+    keys: Vec<Option<T>>,
+    cmp: C,
+}
+
+pub type IndexMinPQ<T> = IndexPQ<T, Min>;
+pub type IndexMaxPQ<T> = IndexPQ<T, Max>;
+
+impl<T, C: Compare<T> + Default> IndexPQ<T, C> {
+    pub fn new() -> IndexPQ<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for IndexPQ<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> IndexPQ<T, C> {
+    pub fn with_comparator(cmp: C) -> IndexPQ<T, C> {
+        IndexPQ {
+            pq: vec![],
+            qp: vec![],
+            keys: vec![],
+            cmp,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pq.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pq.len()
+    }
+
+    pub fn contains(&self, handle: usize) -> bool {
+        self.position(handle).is_some()
+    }
+
+    pub fn key_of(&self, handle: usize) -> Option<&T> {
+        self.keys.get(handle).and_then(|key| key.as_ref())
+    }
+
+    pub fn insert(&mut self, handle: usize, key: T) {
+        assert!(!self.contains(handle), "handle is already in the queue");
+        if handle >= self.qp.len() {
+            self.qp.resize(handle + 1, None);
+            self.keys.resize_with(handle + 1, || None);
+        }
+        self.pq.push(handle);
+        self.qp[handle] = Some(self.pq.len() - 1);
+        self.keys[handle] = Some(key);
+        self.swim(self.pq.len() - 1);
+    }
+
+    pub fn peek(&self) -> Option<(usize, &T)> {
+        let handle = *self.pq.first()?;
+        Some((handle, self.keys[handle].as_ref().unwrap()))
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        let handle = *self.pq.first()?;
+        self.delete(handle).map(|key| (handle, key))
+    }
+
+    pub fn change_key(&mut self, handle: usize, key: T) {
+        let i = self.position(handle).expect("handle is not in the queue");
+        self.keys[handle] = Some(key);
+        self.swim(i);
+        self.sink(self.qp[handle].unwrap());
+    }
+
+    pub fn delete(&mut self, handle: usize) -> Option<T> {
+        let i = self.position(handle)?;
+        let last = self.pq.len() - 1;
+        self.exchange(i, last);
+        self.pq.pop();
+        self.qp[handle] = None;
+        if i < last {
+            let moved = self.pq[i];
+            self.swim(i);
+            self.sink(self.qp[moved].unwrap());
+        }
+        self.keys[handle].take()
+    }
+
+    // moves the key of `handle` towards the top, checked with the queue's
+    // own comparator so only the swim is needed
+    fn raise(&mut self, handle: usize, key: T, msg: &str) {
+        let i = self.position(handle).expect("handle is not in the queue");
+        let old = self.keys[handle].as_ref().unwrap();
+        assert!(self.cmp.compare(&key, old) == Ordering::Greater, "{}", msg);
+        self.keys[handle] = Some(key);
+        self.swim(i);
+    }
+
+    // moves the key of `handle` away from the top, only the sink is needed
+    fn lower(&mut self, handle: usize, key: T, msg: &str) {
+        let i = self.position(handle).expect("handle is not in the queue");
+        let old = self.keys[handle].as_ref().unwrap();
+        assert!(self.cmp.compare(&key, old) == Ordering::Less, "{}", msg);
+        self.keys[handle] = Some(key);
+        self.sink(i);
+    }
+
+    fn position(&self, handle: usize) -> Option<usize> {
+        self.qp.get(handle).copied().flatten()
+    }
+}
+
+impl<T: Ord> IndexMinPQ<T> {
+    pub fn min(&self) -> Option<(usize, &T)> {
+        self.peek()
+    }
+
+    pub fn pop_min(&mut self) -> Option<(usize, T)> {
+        self.pop()
+    }
+
+    pub fn decrease_key(&mut self, handle: usize, key: T) {
+        self.raise(handle, key, "key does not decrease");
+    }
+
+    pub fn increase_key(&mut self, handle: usize, key: T) {
+        self.lower(handle, key, "key does not increase");
+    }
+}
+
+impl<T: Ord> IndexMaxPQ<T> {
+    pub fn max(&self) -> Option<(usize, &T)> {
+        self.peek()
+    }
+
+    pub fn pop_max(&mut self) -> Option<(usize, T)> {
+        self.pop()
+    }
+
+    pub fn increase_key(&mut self, handle: usize, key: T) {
+        self.raise(handle, key, "key does not increase");
+    }
+
+    pub fn decrease_key(&mut self, handle: usize, key: T) {
+        self.lower(handle, key, "key does not decrease");
+    }
+}
+
+impl<T, C: Compare<T>> HeapOrder for IndexPQ<T, C> {
+    fn size(&self) -> usize {
+        self.pq.len()
+    }
+
+    fn greater(&self, i: usize, j: usize) -> bool {
+        let a = self.keys[self.pq[i]].as_ref().unwrap();
+        let b = self.keys[self.pq[j]].as_ref().unwrap();
+        self.cmp.compare(a, b) == Ordering::Greater
+    }
+
+    fn exchange(&mut self, i: usize, j: usize) {
+        self.pq.swap(i, j);
+        self.qp[self.pq[i]] = Some(i);
+        self.qp[self.pq[j]] = Some(j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pop_min() {
+        let mut pq = IndexMinPQ::new();
+        for (i, &key) in [40, 10, 30, 20, 50].iter().enumerate() {
+            pq.insert(i, key);
+        }
+        assert_eq!(pq.len(), 5);
+        assert_eq!(pq.min(), Some((1, &10)));
+        assert_eq!(pq.pop_min(), Some((1, 10)));
+        assert_eq!(pq.pop_min(), Some((3, 20)));
+        assert!(!pq.contains(3));
+        assert!(pq.contains(0));
+        assert_eq!(pq.pop_min(), Some((2, 30)));
+        assert_eq!(pq.pop_min(), Some((0, 40)));
+        assert_eq!(pq.pop_min(), Some((4, 50)));
+        assert_eq!(pq.pop_min(), None);
+    }
+
+    #[test]
+    fn test_change_key() {
+        let mut pq = IndexMinPQ::new();
+        pq.insert(7, 70);
+        pq.insert(3, 30);
+        pq.insert(5, 50);
+        pq.decrease_key(7, 10);
+        assert_eq!(pq.min(), Some((7, &10)));
+        pq.increase_key(7, 90);
+        assert_eq!(pq.min(), Some((3, &30)));
+        pq.change_key(5, 5);
+        assert_eq!(pq.key_of(5), Some(&5));
+        assert_eq!(pq.pop_min(), Some((5, 5)));
+        assert_eq!(pq.pop_min(), Some((3, 30)));
+        assert_eq!(pq.pop_min(), Some((7, 90)));
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut pq = IndexMaxPQ::new();
+        for i in 0..10 {
+            pq.insert(i, i * 3 % 10);
+        }
+        assert_eq!(pq.delete(3), Some(9));
+        assert_eq!(pq.delete(3), None);
+        assert_eq!(pq.delete(100), None);
+        assert_eq!(pq.max(), Some((6, &8)));
+        let mut keys = vec![];
+        while let Some((_, key)) = pq.pop_max() {
+            keys.push(key);
+        }
+        assert_eq!(keys, [8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+        pq.insert(3, 1);
+        assert_eq!(pq.key_of(3), Some(&1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decrease_key_panics_on_larger_key() {
+        let mut pq = IndexMinPQ::new();
+        pq.insert(0, 1);
+        pq.decrease_key(0, 2);
+    }
+
+    #[test]
+    fn test_max_keys() {
+        let mut pq = IndexMaxPQ::new();
+        pq.insert(0, 10);
+        pq.insert(1, 20);
+        pq.insert(2, 30);
+        pq.increase_key(0, 40);
+        assert_eq!(pq.max(), Some((0, &40)));
+        pq.decrease_key(0, 5);
+        assert_eq!(pq.max(), Some((2, &30)));
+        assert_eq!(pq.pop_max(), Some((2, 30)));
+        assert_eq!(pq.pop_max(), Some((1, 20)));
+        assert_eq!(pq.pop_max(), Some((0, 5)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_max_increase_key_panics_on_smaller_key() {
+        let mut pq = IndexMaxPQ::new();
+        pq.insert(0, 2);
+        pq.increase_key(0, 1);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // (from, to, weight)
+        let edges = [
+            (0, 1, 4),
+            (0, 2, 1),
+            (2, 1, 2),
+            (1, 3, 1),
+            (2, 3, 5),
+            (3, 4, 3),
+        ];
+        let mut dist = vec![None; 5];
+        let mut pq = IndexMinPQ::new();
+        pq.insert(0, 0);
+        while let Some((v, d)) = pq.pop_min() {
+            dist[v] = Some(d);
+            for &(_, w, weight) in edges.iter().filter(|e| e.0 == v) {
+                if dist[w].is_some() {
+                    continue;
+                }
+                match pq.key_of(w) {
+                    None => pq.insert(w, d + weight),
+                    Some(&old) if d + weight < old => pq.decrease_key(w, d + weight),
+                    Some(_) => {}
+                }
+            }
+        }
+        assert_eq!(dist, [Some(0), Some(3), Some(1), Some(4), Some(7)]);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::structure::binary_heap::{Compare, Max};
+use crate::structure::priority_queue::{MeldableHeap, PriorityQueue};
+use std::cmp::Ordering;
+use std::mem;
+
+// the right spine is kept shortest, so melding walks O(log n) nodes
+pub struct LeftistHeap<T, C = Max> {
+    root: Link<T>,
+    len: usize,
+    cmp: C,
+}
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    elem: T,
+    rank: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+fn rank<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.rank)
+}
+
+fn merge<T, C: Compare<T>>(cmp: &C, a: Link<T>, b: Link<T>) -> Link<T> {
+    match (a, b) {
+        (None, b) => b,
+        (a, None) => a,
+        (Some(mut a), Some(mut b)) => {
+            if cmp.compare(&b.elem, &a.elem) == Ordering::Greater {
+                mem::swap(&mut a, &mut b);
+            }
+            let right = a.right.take();
+            a.right = merge(cmp, right, Some(b));
+            if rank(&a.left) < rank(&a.right) {
+                mem::swap(&mut a.left, &mut a.right);
+            }
+            a.rank = rank(&a.right) + 1;
+            Some(a)
+        }
+    }
+}
+
+impl<T, C: Compare<T> + Default> LeftistHeap<T, C> {
+    pub fn new() -> LeftistHeap<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for LeftistHeap<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> LeftistHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> LeftistHeap<T, C> {
+        LeftistHeap {
+            root: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.elem)
+    }
+
+    pub fn push(&mut self, x: T) {
+        let node = Box::new(Node {
+            elem: x,
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        self.root = merge(&self.cmp, self.root.take(), Some(node));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let mut root = self.root.take()?;
+        self.root = merge(&self.cmp, root.left.take(), root.right.take());
+        self.len -= 1;
+        Some(root.elem)
+    }
+
+    pub fn meld(&mut self, mut other: LeftistHeap<T, C>) {
+        self.root = merge(&self.cmp, self.root.take(), other.root.take());
+        self.len += mem::replace(&mut other.len, 0);
+    }
+}
+
+// the left spine may be long, so drop without recursion
+impl<T, C> Drop for LeftistHeap<T, C> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for LeftistHeap<T, C> {
+    fn push(&mut self, x: T) {
+        LeftistHeap::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        LeftistHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        LeftistHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        LeftistHeap::len(self)
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for LeftistHeap<T, C> {
+    fn meld(&mut self, other: Self) {
+        LeftistHeap::meld(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::binary_heap::Min;
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = LeftistHeap::<i32>::new();
+        for &x in &[4, 5, 1, 2, 4] {
+            heap.push(x);
+        }
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(2));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = LeftistHeap::<i32, Min>::new();
+        let mut b = LeftistHeap::<i32, Min>::new();
+        for i in 0..50 {
+            a.push(i * 2);
+            b.push(i * 2 + 1);
+        }
+        a.meld(b);
+        assert_eq!(a.len(), 100);
+        for i in 0..100 {
+            assert_eq!(a.pop(), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_long_spine_drop() {
+        let mut heap = LeftistHeap::<i32, Min>::new();
+        let n = if cfg!(miri) { 2_000 } else { 200_000 };
+        for i in (0..n).rev() {
+            heap.push(i);
+        }
+        assert_eq!(heap.peek(), Some(&0));
+    }
+}
@@ -1,31 +1,41 @@
-use std::sync::Arc;
+use crate::structure::shared_pointer::{ArcKind, RcKind, SharedPointerKind};
+use std::fmt;
+use std::iter::FromIterator;
 
-pub struct List<T> {
-    head: Link<T>,
+pub struct List<T, P: SharedPointerKind = ArcKind> {
+    head: Link<T, P>,
 }
 
-type Link<T> = Option<Arc<Node<T>>>;
+pub type RcList<T> = List<T, RcKind>;
+pub type ArcList<T> = List<T, ArcKind>;
 
-struct Node<T> {
+type Link<T, P> = Option<<P as SharedPointerKind>::Pointer<Node<T, P>>>;
+
+struct Node<T, P: SharedPointerKind> {
     elem: T,
-    next: Link<T>,
+    next: Link<T, P>,
 }
 
-impl<T> List<T> {
+impl<T, P: SharedPointerKind> List<T, P> {
     pub fn new() -> Self {
         List { head: None }
     }
 
-    pub fn append(&self, elem: T) -> List<T> {
+    pub fn cons(&self, elem: T) -> List<T, P> {
         List {
-            head: Some(Arc::new(Node {
-                elem: elem,
+            head: Some(P::new(Node {
+                elem,
                 next: self.head.clone(),
             })),
         }
     }
 
-    pub fn tail(&self) -> List<T> {
+    // same as `cons`, kept for existing callers
+    pub fn append(&self, elem: T) -> List<T, P> {
+        self.cons(elem)
+    }
+
+    pub fn tail(&self) -> List<T, P> {
         List {
             head: self.head.as_ref().and_then(|node| node.next.clone()),
         }
@@ -35,35 +45,197 @@ impl<T> List<T> {
         self.head.as_ref().map(|node| &node.elem)
     }
 
-    pub fn iter(&self) -> Iter<'_, T> {
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    pub fn fold<B, F>(&self, init: B, f: F) -> B
+    where
+        F: FnMut(B, &T) -> B,
+    {
+        self.iter().fold(init, f)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> List<U, P>
+    where
+        F: FnMut(&T) -> U,
+    {
+        self.iter().map(f).collect()
+    }
+
+    // shares the nodes after the first `n`
+    pub fn drop(&self, n: usize) -> List<T, P> {
+        let mut link = self.head.as_ref();
+        for _ in 0..n {
+            match link {
+                Some(node) => link = node.next.as_ref(),
+                None => break,
+            }
+        }
+        List {
+            head: link.cloned(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
         Iter {
-            next: self.head.as_ref().map(|node| &**node),
+            next: self.head.as_deref(),
+        }
+    }
+
+    // links `elems` in front of `rest`, keeping their order
+    fn prepend_all(rest: Link<T, P>, elems: Vec<T>) -> List<T, P> {
+        let mut list = List { head: rest };
+        for elem in elems.into_iter().rev() {
+            list = list.cons(elem);
+        }
+        list
+    }
+}
+
+impl<T: Clone, P: SharedPointerKind> List<T, P> {
+    pub fn reverse(&self) -> List<T, P> {
+        self.fold(List::new(), |list, elem| list.cons(elem.clone()))
+    }
+
+    // copies `self`, shares `other`
+    pub fn concat(&self, other: &List<T, P>) -> List<T, P> {
+        List::prepend_all(other.head.clone(), self.iter().cloned().collect())
+    }
+
+    pub fn filter<F>(&self, mut f: F) -> List<T, P>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().filter(|elem| f(elem)).cloned().collect()
+    }
+
+    pub fn take(&self, n: usize) -> List<T, P> {
+        self.iter().take(n).cloned().collect()
+    }
+
+    pub fn zip<U: Clone>(&self, other: &List<U, P>) -> List<(T, U), P> {
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| (a.clone(), b.clone()))
+            .collect()
+    }
+}
+
+impl<T, P: SharedPointerKind> Default for List<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P: SharedPointerKind> Clone for List<T, P> {
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
         }
     }
 }
 
-pub struct Iter<'a, T> {
-    next: Option<&'a Node<T>>,
+impl<T, P: SharedPointerKind> Drop for List<T, P> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some(node) = head {
+            match P::try_unwrap(node) {
+                Ok(mut node) => head = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T: PartialEq, P: SharedPointerKind> PartialEq for List<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: SharedPointerKind> Eq for List<T, P> {}
+
+impl<T: fmt::Debug, P: SharedPointerKind> fmt::Debug for List<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, P: SharedPointerKind> FromIterator<T> for List<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        List::prepend_all(None, iter.into_iter().collect())
+    }
+}
+
+pub struct Iter<'a, T, P: SharedPointerKind> {
+    next: Option<&'a Node<T, P>>,
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
+impl<'a, T, P: SharedPointerKind> Iterator for Iter<'a, T, P> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.next.map(|node| {
-            self.next = node.next.as_ref().map(|node| &**node);
+            self.next = node.next.as_deref();
             &node.elem
         })
     }
 }
 
+impl<'a, T, P: SharedPointerKind> IntoIterator for &'a List<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, P>;
+
+    fn into_iter(self) -> Iter<'a, T, P> {
+        self.iter()
+    }
+}
+
+pub struct IntoIter<T, P: SharedPointerKind>(List<T, P>);
+
+impl<T: Clone, P: SharedPointerKind> Iterator for IntoIter<T, P> {
+    type Item = T;
+
+    // moves elements out of uniquely owned nodes, clones from shared ones
+    fn next(&mut self) -> Option<T> {
+        self.0.head.take().map(|node| match P::try_unwrap(node) {
+            Ok(mut node) => {
+                self.0.head = node.next.take();
+                node.elem
+            }
+            Err(node) => {
+                self.0.head = node.next.clone();
+                node.elem.clone()
+            }
+        })
+    }
+}
+
+impl<T: Clone, P: SharedPointerKind> IntoIterator for List<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, P>;
+
+    fn into_iter(self) -> IntoIter<T, P> {
+        IntoIter(self)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::List;
+    use super::{ArcList, List, RcList};
+    use crate::structure::shared_pointer::{ArcKind, RcKind, SharedPointerKind};
 
-    #[test]
-    fn basics() {
-        let list = List::new();
+    fn basics<P: SharedPointerKind>() {
+        let list: List<i32, P> = List::new();
         assert_eq!(list.head(), None);
 
         let list = list.append(1).append(2).append(3);
@@ -83,13 +255,102 @@ mod test {
         assert_eq!(list.head(), None);
     }
 
-    #[test]
-    fn iter() {
-        let list = List::new().append(1).append(2).append(3);
+    fn iter<P: SharedPointerKind>() {
+        let list: List<i32, P> = List::new().append(1).append(2).append(3);
 
         let mut iter = list.iter();
         assert_eq!(iter.next(), Some(&3));
         assert_eq!(iter.next(), Some(&2));
         assert_eq!(iter.next(), Some(&1));
     }
+
+    fn from_iter<P: SharedPointerKind>() {
+        let list: List<i32, P> = vec![1, 2, 3].into_iter().collect();
+        assert_eq!(list, List::new().cons(3).cons(2).cons(1));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(list.len(), 3);
+        assert!(!list.is_empty());
+        assert!(List::<i32, P>::new().is_empty());
+    }
+
+    fn transform<P: SharedPointerKind>() {
+        let list: List<i32, P> = (1..=5).collect();
+        assert_eq!(list.reverse(), (1..=5).rev().collect());
+        assert_eq!(list.map(|x| x * 10), (1..=5).map(|x| x * 10).collect());
+        assert_eq!(
+            list.filter(|x| x % 2 == 1),
+            vec![1, 3, 5].into_iter().collect()
+        );
+        assert_eq!(list.fold(0, |acc, x| acc + x), 15);
+        assert_eq!(list.take(2), (1..=2).collect());
+        assert_eq!(list.drop(3), (4..=5).collect());
+        assert_eq!(list.drop(9), List::new());
+        assert_eq!(list.nth(1), Some(&2));
+        assert_eq!(list.nth(5), None);
+    }
+
+    fn concat_zip<P: SharedPointerKind>() {
+        let a: List<i32, P> = (1..=2).collect();
+        let b: List<i32, P> = (3..=4).collect();
+        assert_eq!(a.concat(&b), (1..=4).collect());
+        assert_eq!(a.concat(&List::new()), a);
+
+        let c: List<char, P> = vec!['a', 'b', 'c'].into_iter().collect();
+        assert_eq!(a.zip(&c), vec![(1, 'a'), (2, 'b')].into_iter().collect());
+    }
+
+    fn into_iter<P: SharedPointerKind>() {
+        let list: List<String, P> = vec!["a".to_string(), "b".to_string()].into_iter().collect();
+        let shared = list.tail();
+        let xs: Vec<String> = list.into_iter().collect();
+        assert_eq!(xs, vec!["a", "b"]);
+        assert_eq!(shared.head().map(|s| s.as_str()), Some("b"));
+    }
+
+    fn long_list_drop<P: SharedPointerKind>() {
+        let n = if cfg!(miri) { 2_000 } else { 200_000 };
+        let list: List<i32, P> = (0..n).collect();
+        let shared = list.drop(n as usize / 2);
+        drop(list);
+        assert_eq!(shared.head(), Some(&(n / 2)));
+    }
+
+    macro_rules! both_kinds {
+        ($($name:ident),*) => {
+            $(
+                #[test]
+                fn $name() {
+                    super::$name::<RcKind>();
+                    super::$name::<ArcKind>();
+                }
+            )*
+        };
+    }
+
+    mod rc_and_arc {
+        use super::*;
+
+        both_kinds!(
+            basics,
+            iter,
+            from_iter,
+            transform,
+            concat_zip,
+            into_iter,
+            long_list_drop
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let arc: ArcList<i32> = (1..=3).collect();
+        let rc: RcList<i32> = (1..=3).collect();
+        assert_send_sync(&arc);
+        assert!(arc.iter().eq(rc.iter()));
+
+        let default: List<i32> = arc.clone();
+        assert_eq!(default, arc);
+    }
 }
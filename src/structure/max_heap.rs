@@ -1,91 +1,15 @@
-#[derive(Default)]
-pub struct MaxHeap<T: Ord> {
-    heap: Vec<T>,
-}
-
-impl<T: Ord> MaxHeap<T> {
-    pub fn new() -> MaxHeap<T> {
-        MaxHeap { heap: vec![] }
-    }
-
-    pub fn init(mut self, xs: Vec<T>) -> MaxHeap<T> {
-        self.heap = xs;
-        for i in (0..self.len()).rev() {
-            self.sink(i);
-        }
-        self
-    }
+use crate::structure::binary_heap::{BinaryHeap, Max};
 
-    pub fn is_empty(&self) -> bool {
-        self.heap.is_empty()
-    }
-
-    pub fn len(&self) -> usize {
-        self.heap.len()
-    }
+pub type MaxHeap<T> = BinaryHeap<T, Max>;
 
+impl<T: Ord> MaxHeap<T> {
     pub fn maxmium(&self) -> Option<&T> {
-        self.heap.first()
+        self.peek()
     }
 
     pub fn pop_max(&mut self) -> Option<T> {
-        if self.is_empty() {
-            return None;
-        }
-
-        let max = self.heap.swap_remove(0);
-        if !self.is_empty() {
-            self.sink(0);
-        }
-
-        Some(max)
+        self.pop()
     }
-
-    pub fn push(&mut self, x: T) {
-        self.heap.push(x);
-        self.swim(self.len() - 1);
-    }
-
-    fn sink(&mut self, i: usize) {
-        let mut i = i;
-        while i < self.len() {
-            let l = left(i);
-            let r = right(i);
-            let mut largest = i;
-            if l < self.len() && self.heap[l] > self.heap[largest] {
-                largest = l;
-            }
-            if r < self.len() && self.heap[r] > self.heap[largest] {
-                largest = r;
-            }
-            if largest != i {
-                self.heap.swap(i, largest);
-                i = largest;
-            } else {
-                break;
-            }
-        }
-    }
-
-    fn swim(&mut self, i: usize) {
-        let mut i = i;
-        while i != 0 && self.heap[parent(i)] < self.heap[i] {
-            self.heap.swap(i, parent(i));
-            i = parent(i);
-        }
-    }
-}
-
-fn parent(i: usize) -> usize {
-    (i - 1) / 2
-}
-
-fn left(i: usize) -> usize {
-    2 * i + 1
-}
-
-fn right(i: usize) -> usize {
-    2 * i + 2
 }
 
 #[cfg(test)]
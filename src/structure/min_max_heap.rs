@@ -0,0 +1,266 @@
+use crate::structure::binary_heap::{Compare, Max};
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+// double ended priority queue in one array, nodes on even levels are the
+// least of their subtree and nodes on odd levels the greatest
+pub struct MinMaxHeap<T, C = Max> {
+    heap: Vec<T>,
+    cmp: C,
+}
+
+impl<T, C: Compare<T> + Default> MinMaxHeap<T, C> {
+    pub fn new() -> MinMaxHeap<T, C> {
+        Self::with_comparator(C::default())
+    }
+
+    pub fn with_capacity(capacity: usize) -> MinMaxHeap<T, C> {
+        let mut heap = Self::new();
+        heap.heap.reserve(capacity);
+        heap
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for MinMaxHeap<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> MinMaxHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> MinMaxHeap<T, C> {
+        MinMaxHeap { heap: vec![], cmp }
+    }
+
+    pub fn init(mut self, xs: Vec<T>) -> MinMaxHeap<T, C> {
+        self.heap = xs;
+        for i in (0..self.len() / 2).rev() {
+            self.push_down(i);
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn peek_min(&self) -> Option<&T> {
+        self.heap.first()
+    }
+
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.heap[i])
+    }
+
+    pub fn push(&mut self, x: T) {
+        self.heap.push(x);
+        self.push_up(self.len() - 1);
+    }
+
+    pub fn pop_min(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    pub fn pop_max(&mut self) -> Option<T> {
+        let i = self.max_index()?;
+        self.remove(i)
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+    }
+
+    fn max_index(&self) -> Option<usize> {
+        match self.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ if self.less(1, 2) => Some(2),
+            _ => Some(1),
+        }
+    }
+
+    fn remove(&mut self, i: usize) -> Option<T> {
+        if i >= self.len() {
+            return None;
+        }
+        let x = self.heap.swap_remove(i);
+        if i < self.len() {
+            self.push_down(i);
+        }
+        Some(x)
+    }
+
+    fn less(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(&self.heap[i], &self.heap[j]) == Ordering::Less
+    }
+
+    // compares towards the top of the level's order, `less` on min levels
+    fn before(&self, i: usize, j: usize, min_level: bool) -> bool {
+        if min_level {
+            self.less(i, j)
+        } else {
+            self.less(j, i)
+        }
+    }
+
+    fn push_up(&mut self, i: usize) {
+        if i == 0 {
+            return;
+        }
+        let parent = (i - 1) / 2;
+        let min_level = is_min_level(i);
+        if self.before(parent, i, min_level) {
+            self.heap.swap(i, parent);
+            self.push_up_grandparents(parent, !min_level);
+        } else {
+            self.push_up_grandparents(i, min_level);
+        }
+    }
+
+    fn push_up_grandparents(&mut self, mut i: usize, min_level: bool) {
+        while i > 2 {
+            let grandparent = ((i - 1) / 2 - 1) / 2;
+            if !self.before(i, grandparent, min_level) {
+                break;
+            }
+            self.heap.swap(i, grandparent);
+            i = grandparent;
+        }
+    }
+
+    fn push_down(&mut self, mut i: usize) {
+        let min_level = is_min_level(i);
+        loop {
+            let first_child = 2 * i + 1;
+            if first_child >= self.len() {
+                return;
+            }
+
+            // the best of the children and grandchildren
+            let first_grandchild = 2 * first_child + 1;
+            let mut m = first_child;
+            let candidates =
+                (first_child + 1..first_child + 2).chain(first_grandchild..first_grandchild + 4);
+            for j in candidates.filter(|&j| j < self.len()) {
+                if self.before(j, m, min_level) {
+                    m = j;
+                }
+            }
+
+            if !self.before(m, i, min_level) {
+                return;
+            }
+            self.heap.swap(i, m);
+            if m < first_grandchild {
+                return;
+            }
+            let parent = (m - 1) / 2;
+            if self.before(parent, m, min_level) {
+                self.heap.swap(m, parent);
+            }
+            i = m;
+        }
+    }
+}
+
+fn is_min_level(i: usize) -> bool {
+    // depth of i is floor(log2(i + 1))
+    (usize::BITS - 1 - (i + 1).leading_zeros()).is_multiple_of(2)
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for MinMaxHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        MinMaxHeap::new().init(iter.into_iter().collect())
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::structure::binary_heap::Min;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn test_levels() {
+        let levels: Vec<bool> = (0..8).map(is_min_level).collect();
+        assert_eq!(levels, [true, false, false, true, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap: MinMaxHeap<i32> = MinMaxHeap::new();
+        assert_eq!(heap.peek_min(), None);
+        assert_eq!(heap.peek_max(), None);
+        for &x in &[4, 5, 1, 2, 4, 9, 0] {
+            heap.push(x);
+        }
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek_min(), Some(&0));
+        assert_eq!(heap.peek_max(), Some(&9));
+        assert_eq!(heap.pop_max(), Some(9));
+        assert_eq!(heap.pop_min(), Some(0));
+        assert_eq!(heap.pop_min(), Some(1));
+        assert_eq!(heap.pop_max(), Some(5));
+        assert_eq!(heap.pop_max(), Some(4));
+        assert_eq!(heap.pop_min(), Some(2));
+        assert_eq!(heap.pop_min(), Some(4));
+        assert_eq!(heap.pop_max(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_comparator() {
+        let mut heap: MinMaxHeap<i32, Min> = (1..=5).collect();
+        assert_eq!(heap.pop_min(), Some(5));
+        assert_eq!(heap.pop_max(), Some(1));
+    }
+
+    #[test]
+    fn test_random() {
+        let mut rng = XorShift::default();
+        let xs: Vec<u64> = (0..500).map(|_| rng.next_u64() % 100).collect();
+        let mut heap: MinMaxHeap<u64> = xs[..250].iter().cloned().collect();
+        heap.extend(xs[250..].iter().cloned());
+
+        let mut sorted = xs.clone();
+        sorted.sort();
+        let mut expected: VecDeque<u64> = sorted.into();
+        while !expected.is_empty() {
+            assert_eq!(heap.peek_min(), expected.front());
+            assert_eq!(heap.peek_max(), expected.back());
+            if rng.next_u64().is_multiple_of(2) {
+                assert_eq!(heap.pop_min(), expected.pop_front());
+            } else {
+                assert_eq!(heap.pop_max(), expected.pop_back());
+            }
+            if rng.next_u64().is_multiple_of(4) {
+                let x = rng.next_u64() % 100;
+                heap.push(x);
+                let i = expected
+                    .iter()
+                    .position(|&y| y > x)
+                    .unwrap_or(expected.len());
+                expected.insert(i, x);
+            }
+        }
+        assert!(heap.is_empty());
+    }
+}
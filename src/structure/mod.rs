@@ -1,7 +1,26 @@
+pub mod binary_heap;
+pub mod binomial_heap;
+pub mod collection;
+pub mod dary_heap;
+pub mod fibonacci_heap;
 pub mod max_heap;
 pub mod min_heap;
+pub mod min_max_heap;
+pub mod hamt;
+pub mod heap_util;
+pub mod index_pq;
+pub mod leftist_heap;
+pub mod pairing_heap;
+pub mod priority_queue;
+pub mod stable_priority_queue;
 pub mod list;
 pub mod deque;
+pub mod shared_pointer;
+pub mod vector;
 pub mod unsafe_stack;
 pub mod unsafe_deque;
-pub mod unsafe_red_black_tree;
\ No newline at end of file
+pub mod unsafe_red_black_tree;
+#[cfg(test)]
+mod differential;
+#[cfg(test)]
+mod drop_counter;
\ No newline at end of file
@@ -0,0 +1,183 @@
+use crate::structure::binary_heap::{Compare, Max};
+use crate::structure::priority_queue::{MeldableHeap, PriorityQueue};
+use std::cmp::Ordering;
+use std::mem;
+
+// O(1) push and meld, pop pairs up the root's children in two passes
+pub struct PairingHeap<T, C = Max> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+    cmp: C,
+}
+
+struct Node<T> {
+    elem: T,
+    children: Vec<Box<Node<T>>>,
+}
+
+fn link<T, C: Compare<T>>(cmp: &C, mut a: Box<Node<T>>, mut b: Box<Node<T>>) -> Box<Node<T>> {
+    if cmp.compare(&b.elem, &a.elem) == Ordering::Greater {
+        mem::swap(&mut a, &mut b);
+    }
+    a.children.push(b);
+    a
+}
+
+fn merge<T, C: Compare<T>>(
+    cmp: &C,
+    a: Option<Box<Node<T>>>,
+    b: Option<Box<Node<T>>>,
+) -> Option<Box<Node<T>>> {
+    match (a, b) {
+        (None, b) => b,
+        (a, None) => a,
+        (Some(a), Some(b)) => Some(link(cmp, a, b)),
+    }
+}
+
+impl<T, C: Compare<T> + Default> PairingHeap<T, C> {
+    pub fn new() -> PairingHeap<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for PairingHeap<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C: Compare<T>> PairingHeap<T, C> {
+    pub fn with_comparator(cmp: C) -> PairingHeap<T, C> {
+        PairingHeap {
+            root: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.elem)
+    }
+
+    pub fn push(&mut self, x: T) {
+        let node = Box::new(Node {
+            elem: x,
+            children: vec![],
+        });
+        self.root = merge(&self.cmp, self.root.take(), Some(node));
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        let root = *self.root.take()?;
+
+        // first pass pairs children left to right, second melds the pairs right to left
+        let mut pairs = Vec::with_capacity(root.children.len() / 2 + 1);
+        let mut children = root.children.into_iter();
+        while let Some(a) = children.next() {
+            pairs.push(merge(&self.cmp, Some(a), children.next()));
+        }
+        while let Some(pair) = pairs.pop() {
+            self.root = merge(&self.cmp, pair, self.root.take());
+        }
+
+        self.len -= 1;
+        Some(root.elem)
+    }
+
+    pub fn meld(&mut self, mut other: PairingHeap<T, C>) {
+        self.root = merge(&self.cmp, self.root.take(), other.root.take());
+        self.len += mem::replace(&mut other.len, 0);
+    }
+}
+
+// trees may be arbitrarily deep, so drop without recursion
+impl<T, C> Drop for PairingHeap<T, C> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for PairingHeap<T, C> {
+    fn push(&mut self, x: T) {
+        PairingHeap::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+}
+
+impl<T, C: Compare<T>> MeldableHeap<T> for PairingHeap<T, C> {
+    fn meld(&mut self, other: Self) {
+        PairingHeap::meld(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structure::binary_heap::Min;
+
+    #[test]
+    fn test_push_pop() {
+        let mut heap = PairingHeap::<i32>::new();
+        for &x in &[4, 5, 1, 2, 4] {
+            heap.push(x);
+        }
+        assert_eq!(heap.len(), 5);
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.pop(), Some(5));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(4));
+        assert_eq!(heap.pop(), Some(2));
+        assert_eq!(heap.pop(), Some(1));
+        assert_eq!(heap.pop(), None);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn test_meld() {
+        let mut a = PairingHeap::<i32, Min>::new();
+        let mut b = PairingHeap::<i32, Min>::new();
+        for i in 0..50 {
+            a.push(i * 2);
+            b.push(i * 2 + 1);
+        }
+        a.meld(b);
+        assert_eq!(a.len(), 100);
+        for i in 0..100 {
+            assert_eq!(a.pop(), Some(i));
+        }
+    }
+
+    #[test]
+    fn test_deep_tree_drop() {
+        let mut heap = PairingHeap::<i32>::new();
+        let n = if cfg!(miri) { 2_000 } else { 200_000 };
+        for i in 0..n {
+            heap.push(i);
+        }
+        assert_eq!(heap.peek(), Some(&(n - 1)));
+    }
+}
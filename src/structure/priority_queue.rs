@@ -0,0 +1,135 @@
+use crate::structure::binary_heap::Compare;
+use crate::structure::dary_heap::DaryHeap;
+
+// operations shared by every heap, `pop` returns the top by the heap's comparator
+pub trait PriorityQueue<T> {
+    fn push(&mut self, x: T);
+
+    fn pop(&mut self) -> Option<T>;
+
+    fn peek(&self) -> Option<&T>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// heaps that merge with another heap of the same kind, emptying it
+pub trait MeldableHeap<T>: PriorityQueue<T> + Sized {
+    fn meld(&mut self, other: Self);
+}
+
+// covers `BinaryHeap` too, it is the `D = 2` case
+impl<T, const D: usize, C: Compare<T>> PriorityQueue<T> for DaryHeap<T, D, C> {
+    fn push(&mut self, x: T) {
+        DaryHeap::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        DaryHeap::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        DaryHeap::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        DaryHeap::len(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::structure::binary_heap::{BinaryHeap, Max, Min};
+    use crate::structure::binomial_heap::BinomialHeap;
+    use crate::structure::fibonacci_heap::FibonacciHeap;
+    use crate::structure::leftist_heap::LeftistHeap;
+    use crate::structure::max_heap::MaxHeap;
+    use crate::structure::pairing_heap::PairingHeap;
+    use crate::structure::stable_priority_queue::StablePriorityQueue;
+
+    fn keys(n: u32, seed: u64) -> Vec<u32> {
+        let n = if cfg!(miri) { n / 10 } else { n };
+        let mut rng = XorShift::new(seed);
+        (0..n).map(|_| rng.next_u64() as u32).collect()
+    }
+
+    fn drain<T, Q: PriorityQueue<T>>(queue: &mut Q) -> Vec<T> {
+        let mut xs = vec![];
+        while let Some(x) = queue.pop() {
+            xs.push(x);
+        }
+        xs
+    }
+
+    // `order` is the comparator the queue was built with, the top comes first
+    fn check_queue<Q: PriorityQueue<u32> + Default>(order: impl Compare<u32>) {
+        let xs = keys(1000, 7);
+        let mut expected = xs.clone();
+        expected.sort_by(|a, b| order.compare(b, a));
+
+        let mut queue = Q::default();
+        assert!(queue.is_empty());
+        for &x in &xs {
+            queue.push(x);
+        }
+        assert_eq!(queue.len(), xs.len());
+        assert_eq!(queue.peek(), expected.first());
+        assert_eq!(drain(&mut queue), expected);
+        assert_eq!(queue.peek(), None);
+    }
+
+    fn check_meld<Q: MeldableHeap<u32> + Default>(order: impl Compare<u32>) {
+        let (xs, ys) = (keys(300, 1), keys(700, 2));
+        let mut expected: Vec<u32> = xs.iter().chain(ys.iter()).cloned().collect();
+        expected.sort_by(|a, b| order.compare(b, a));
+
+        let mut a = Q::default();
+        let mut b = Q::default();
+        xs.into_iter().for_each(|x| a.push(x));
+        ys.into_iter().for_each(|y| b.push(y));
+        a.meld(b);
+        assert_eq!(a.len(), expected.len());
+        assert_eq!(drain(&mut a), expected);
+    }
+
+    #[test]
+    fn test_array_heaps() {
+        check_queue::<BinaryHeap<u32, Min>>(Min);
+        check_queue::<DaryHeap<u32, 4, Min>>(Min);
+        check_queue::<StablePriorityQueue<u32, Min>>(Min);
+        check_queue::<MaxHeap<u32>>(Max);
+        check_queue::<BinaryHeap<u32, Max>>(Max);
+        check_queue::<DaryHeap<u32, 4, Max>>(Max);
+        check_queue::<DaryHeap<u32, 8>>(Max);
+        check_queue::<StablePriorityQueue<u32, Max>>(Max);
+    }
+
+    #[test]
+    fn test_meldable_heaps() {
+        check_queue::<LeftistHeap<u32, Min>>(Min);
+        check_queue::<BinomialHeap<u32, Min>>(Min);
+        check_queue::<PairingHeap<u32, Min>>(Min);
+        check_meld::<LeftistHeap<u32, Min>>(Min);
+        check_meld::<BinomialHeap<u32, Min>>(Min);
+        check_meld::<PairingHeap<u32, Min>>(Min);
+        check_queue::<FibonacciHeap<u32>>(Min);
+        check_meld::<FibonacciHeap<u32>>(Min);
+    }
+
+    #[test]
+    fn test_max_meldable_heaps() {
+        check_queue::<LeftistHeap<u32, Max>>(Max);
+        check_queue::<BinomialHeap<u32, Max>>(Max);
+        check_queue::<PairingHeap<u32, Max>>(Max);
+        check_queue::<FibonacciHeap<u32, Max>>(Max);
+        check_meld::<LeftistHeap<u32, Max>>(Max);
+        check_meld::<BinomialHeap<u32, Max>>(Max);
+        check_meld::<PairingHeap<u32, Max>>(Max);
+        check_meld::<FibonacciHeap<u32, Max>>(Max);
+    }
+}
@@ -0,0 +1,42 @@
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// selects the reference-counted pointer used by persistent structures
+pub trait SharedPointerKind {
+    type Pointer<T>: Deref<Target = T> + Clone;
+
+    fn new<T>(value: T) -> Self::Pointer<T>;
+
+    fn try_unwrap<T>(ptr: Self::Pointer<T>) -> Result<T, Self::Pointer<T>>;
+}
+
+// single-threaded sharing, no atomic refcount
+pub struct RcKind;
+
+// thread-safe sharing
+pub struct ArcKind;
+
+impl SharedPointerKind for RcKind {
+    type Pointer<T> = Rc<T>;
+
+    fn new<T>(value: T) -> Rc<T> {
+        Rc::new(value)
+    }
+
+    fn try_unwrap<T>(ptr: Rc<T>) -> Result<T, Rc<T>> {
+        Rc::try_unwrap(ptr)
+    }
+}
+
+impl SharedPointerKind for ArcKind {
+    type Pointer<T> = Arc<T>;
+
+    fn new<T>(value: T) -> Arc<T> {
+        Arc::new(value)
+    }
+
+    fn try_unwrap<T>(ptr: Arc<T>) -> Result<T, Arc<T>> {
+        Arc::try_unwrap(ptr)
+    }
+}
@@ -0,0 +1,206 @@
+use crate::structure::binary_heap::{BinaryHeap, Compare, Max, MaxBy, MaxByKey};
+use crate::structure::priority_queue::PriorityQueue;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+// priority queue that pops equal priorities in the order they were pushed,
+// every entry carries a sequence number that breaks ties
+pub struct StablePriorityQueue<T, C = Max> {
+    heap: BinaryHeap<Entry<T>, Fifo<C>>,
+    next_seq: u64,
+}
+
+struct Entry<T> {
+    seq: u64,
+    item: T,
+}
+
+// the wrapped comparator first, then the earlier entry wins
+struct Fifo<C>(C);
+
+impl<T, C: Compare<T>> Compare<Entry<T>> for Fifo<C> {
+    fn compare(&self, a: &Entry<T>, b: &Entry<T>) -> Ordering {
+        self.0
+            .compare(&a.item, &b.item)
+            .then_with(|| b.seq.cmp(&a.seq))
+    }
+}
+
+impl<T, C: Compare<T> + Default> StablePriorityQueue<T, C> {
+    pub fn new() -> StablePriorityQueue<T, C> {
+        Self::with_comparator(C::default())
+    }
+}
+
+impl<T, C: Compare<T> + Default> Default for StablePriorityQueue<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> StablePriorityQueue<T, MaxBy<F>> {
+    pub fn by(f: F) -> StablePriorityQueue<T, MaxBy<F>> {
+        Self::with_comparator(MaxBy(f))
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K> StablePriorityQueue<T, MaxByKey<F>> {
+    pub fn by_key(f: F) -> StablePriorityQueue<T, MaxByKey<F>> {
+        Self::with_comparator(MaxByKey(f))
+    }
+}
+
+impl<T, C: Compare<T>> StablePriorityQueue<T, C> {
+    pub fn with_comparator(cmp: C) -> StablePriorityQueue<T, C> {
+        StablePriorityQueue {
+            heap: BinaryHeap::with_comparator(Fifo(cmp)),
+            next_seq: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|entry| &entry.item)
+    }
+
+    pub fn push(&mut self, item: T) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.heap.push(Entry { seq, item });
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|entry| entry.item)
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.next_seq = 0;
+    }
+
+    // pop order, the top of the queue comes first
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut entries = self.heap.into_sorted_vec();
+        entries.reverse();
+        entries.into_iter().map(|entry| entry.item).collect()
+    }
+}
+
+impl<T, C: Compare<T>> PriorityQueue<T> for StablePriorityQueue<T, C> {
+    fn push(&mut self, x: T) {
+        StablePriorityQueue::push(self, x)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        StablePriorityQueue::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        StablePriorityQueue::peek(self)
+    }
+
+    fn len(&self) -> usize {
+        StablePriorityQueue::len(self)
+    }
+}
+
+impl<T, C: Compare<T> + Default> FromIterator<T> for StablePriorityQueue<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = StablePriorityQueue::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for StablePriorityQueue<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::XorShift;
+    use crate::structure::binary_heap::Min;
+
+    fn drain<T, C: Compare<T>>(queue: &mut StablePriorityQueue<T, C>) -> Vec<T> {
+        let mut xs = vec![];
+        while let Some(x) = queue.pop() {
+            xs.push(x);
+        }
+        xs
+    }
+
+    #[test]
+    fn test_equal_keys_pop_in_insertion_order() {
+        let mut queue = StablePriorityQueue::by_key(|job: &(u8, &str)| job.0);
+        for &job in &[(1, "a"), (2, "b"), (1, "c"), (2, "d"), (1, "e"), (2, "f")] {
+            queue.push(job);
+        }
+        assert_eq!(queue.peek(), Some(&(2, "b")));
+        let names: Vec<&str> = drain(&mut queue).into_iter().map(|job| job.1).collect();
+        assert_eq!(names, ["b", "d", "f", "a", "c", "e"]);
+    }
+
+    #[test]
+    fn test_all_equal_is_fifo() {
+        let mut queue = StablePriorityQueue::by_key(|_: &usize| ());
+        queue.extend(0..1000);
+        assert_eq!(queue.len(), 1000);
+        assert_eq!(drain(&mut queue), (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_min_comparator() {
+        let mut queue = StablePriorityQueue::with_comparator(Min);
+        queue.extend(vec![3, 1, 2]);
+        assert_eq!(queue.pop(), Some(1));
+        queue.push(0);
+        assert_eq!(drain(&mut queue), [0, 2, 3]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_interleaved_matches_stable_sort() {
+        let mut rng = XorShift::new(0x853c_49e6_748f_ea9b);
+        // (priority, insertion number), the reference is a stable sort on priority
+        let mut queue = StablePriorityQueue::by(|a: &(u64, usize), b: &(u64, usize)| a.0.cmp(&b.0));
+        let mut pending: Vec<(u64, usize)> = vec![];
+        let rounds = if cfg!(miri) { 300 } else { 5000 };
+        for i in 0..rounds {
+            if rng.next_u64().is_multiple_of(3) {
+                pending.sort_by_key(|job| std::cmp::Reverse(job.0));
+                let expected = if pending.is_empty() {
+                    None
+                } else {
+                    Some(pending.remove(0))
+                };
+                assert_eq!(queue.pop(), expected);
+            } else {
+                let job = (rng.next_u64() % 5, i);
+                queue.push(job);
+                pending.push(job);
+            }
+        }
+        pending.sort_by_key(|job| std::cmp::Reverse(job.0));
+        assert_eq!(queue.into_sorted_vec(), pending);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut queue: StablePriorityQueue<i32> = (0..5).collect();
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.pop(), None);
+    }
+}
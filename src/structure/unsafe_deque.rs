@@ -11,9 +11,15 @@ struct Node<T> {
     prev: NodePtr<T>,
 }
 
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for Deque<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop_front() {}
+        while self.pop_front().is_some() {}
     }
 }
 
@@ -25,6 +31,10 @@ impl<T> Deque<T> {
         }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
     pub fn push_front(&mut self, elem: T) {
         let mut node = NodePtr::new(elem);
         node.set_next(self.head);
@@ -51,7 +61,9 @@ impl<T> Deque<T> {
         if self.head.is_null() {
             return None;
         }
-        let node = unsafe { Box::from_raw(self.head.0) };
+        // SAFETY: the head is non-null and is unlinked below before
+        // anything else can reach it
+        let node = unsafe { self.head.into_box() };
         self.head.set(node.next);
         if self.head.is_null() {
             self.tail.set(NodePtr::null());
@@ -65,7 +77,9 @@ impl<T> Deque<T> {
         if self.tail.is_null() {
             return None;
         }
-        let node = unsafe { Box::from_raw(self.tail.0) };
+        // SAFETY: the tail is non-null and is unlinked below before
+        // anything else can reach it
+        let node = unsafe { self.tail.into_box() };
         self.tail.set(node.prev);
         if self.tail.is_null() {
             self.head.set(NodePtr::null());
@@ -94,12 +108,15 @@ impl<T> Deque<T> {
 
 impl<T> Clone for NodePtr<T> {
     fn clone(&self) -> NodePtr<T> {
-        Self(self.0)
+        *self
     }
 }
 
 impl<T> Copy for NodePtr<T> {}
 
+// every non-null `NodePtr` points to a node allocated by `NodePtr::new` that
+// is linked into exactly one deque and freed only through `into_box`,
+// references into a node never outlive the borrow of the deque they came from
 impl<T> NodePtr<T> {
     fn null() -> NodePtr<T> {
         Self(core::ptr::null_mut())
@@ -122,32 +139,42 @@ impl<T> NodePtr<T> {
         Self(Box::into_raw(Box::new(node)))
     }
 
+    fn node(&self) -> Option<&Node<T>> {
+        // SAFETY: the pointer is null or points to a live node, and the
+        // deque is borrowed for as long as the reference lives
+        unsafe { self.0.as_ref() }
+    }
+
+    fn node_mut(&mut self) -> Option<&mut Node<T>> {
+        // SAFETY: as in `node`, the deque is mutably borrowed and the
+        // reference is not held across another access to the same node
+        unsafe { self.0.as_mut() }
+    }
+
+    // SAFETY: the pointer must be non-null and the caller must unlink the
+    // node from both neighbours, so nothing reads or frees it afterwards
+    unsafe fn into_box(self) -> Box<Node<T>> {
+        Box::from_raw(self.0)
+    }
+
     fn elem(&self) -> Option<&T> {
-        if self.is_null() {
-            return None;
-        }
-        unsafe { Some(&(*self.0).elem) }
+        self.node().map(|node| &node.elem)
     }
 
     fn elem_mut(&mut self) -> Option<&mut T> {
-        if self.is_null() {
-            return None;
-        }
-        unsafe { Some(&mut (*self.0).elem) }
+        self.node_mut().map(|node| &mut node.elem)
     }
 
     fn set_next(&mut self, next: NodePtr<T>) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.next = next;
         }
-        unsafe { (*self.0).next = next }
     }
 
     fn set_prev(&mut self, prev: NodePtr<T>) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.prev = prev;
         }
-        unsafe { (*self.0).prev = prev }
     }
 }
 
@@ -180,6 +207,7 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 #[cfg(test)]
 mod test {
     use super::Deque;
+    use crate::structure::drop_counter::DropCounter;
 
     #[test]
     fn basics() {
@@ -248,9 +276,9 @@ mod test {
         queue.push_front(2);
         queue.push_front(3);
 
-        assert_eq!(&*queue.peek_front().unwrap(), &3);
+        assert_eq!(queue.peek_front().unwrap(), &3);
         assert_eq!(&mut *queue.peek_front_mut().unwrap(), &mut 3);
-        assert_eq!(&*queue.peek_back().unwrap(), &1);
+        assert_eq!(queue.peek_back().unwrap(), &1);
         assert_eq!(&mut *queue.peek_back_mut().unwrap(), &mut 1);
     }
 
@@ -268,4 +296,44 @@ mod test {
         assert_eq!(iter.next_back(), None);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn drops_each_value_once() {
+        let counter = DropCounter::new();
+        let mut queue = Deque::new();
+        for id in 0..10 {
+            if id % 2 == 0 {
+                queue.push_front(counter.value(id));
+            } else {
+                queue.push_back(counter.value(id));
+            }
+        }
+        assert_eq!(queue.pop_front().map(|x| x.id), Some(8));
+        assert_eq!(queue.pop_back().map(|x| x.id), Some(9));
+        *queue.peek_back_mut().unwrap() = counter.value(10);
+        assert!(counter.dropped(7));
+        assert_eq!(counter.count(), 3);
+
+        let mut iter = queue.into_iter();
+        assert_eq!(iter.next().map(|x| x.id), Some(6));
+        assert_eq!(iter.next_back().map(|x| x.id), Some(10));
+        drop(iter);
+        counter.assert_all_dropped_once(11);
+    }
+
+    #[test]
+    fn drain_from_both_ends() {
+        let counter = DropCounter::new();
+        let mut queue = Deque::new();
+        for id in 0..6 {
+            queue.push_back(counter.value(id));
+        }
+        while queue.pop_front().is_some() && queue.pop_back().is_some() {}
+        assert!(queue.is_empty());
+        assert!(queue.peek_front().is_none());
+        assert!(queue.peek_back().is_none());
+        queue.push_front(counter.value(6));
+        drop(queue);
+        counter.assert_all_dropped_once(7);
+    }
 }
@@ -22,6 +22,12 @@ enum Color {
     Black,
 }
 
+impl<K: Ord, V> Default for RedBlackTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<K: Ord, V> Drop for RedBlackTree<K, V> {
     fn drop(&mut self) {
         self.root.clear()
@@ -37,12 +43,33 @@ impl<K: Ord, V> RedBlackTree<K, V> {
         self.root.count()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.root.is_null()
+    }
+
     pub fn get(&self, key: &K) -> Option<&V> {
         let node_ptr = self.root.get_node_ptr(key);
-        if node_ptr.is_null() {
-            return None;
+        // SAFETY: the node belongs to this tree, which stays borrowed for as
+        // long as the returned reference
+        unsafe { node_ptr.as_ref() }.map(|node| &node.value)
+    }
+
+    pub fn min_key(&self) -> Option<&K> {
+        let mut node = self.root;
+        while !node.left().is_null() {
+            node = node.left();
         }
-        unsafe { Some(&(*node_ptr.0).value) }
+        // SAFETY: as in `get`
+        unsafe { node.as_ref() }.map(|node| &node.key)
+    }
+
+    pub fn max_key(&self) -> Option<&K> {
+        let mut node = self.root;
+        while !node.right().is_null() {
+            node = node.right();
+        }
+        // SAFETY: as in `get`
+        unsafe { node.as_ref() }.map(|node| &node.key)
     }
 
     pub fn put(&mut self, key: K, value: V) {
@@ -53,7 +80,7 @@ impl<K: Ord, V> RedBlackTree<K, V> {
 
 impl<K: Ord, V> Clone for NodePtr<K, V> {
     fn clone(&self) -> NodePtr<K, V> {
-        Self(self.0)
+        *self
     }
 }
 
@@ -80,85 +107,81 @@ impl<K: Ord, V> NodePtr<K, V> {
         Self(Box::into_raw(Box::new(node)))
     }
 
+    // SAFETY: the pointer must be null or point to a node of a tree that
+    // stays borrowed, without mutation, for all of `'a`
+    unsafe fn as_ref<'a>(self) -> Option<&'a Node<K, V>> {
+        self.0.as_ref()
+    }
+
+    fn node(&self) -> Option<&Node<K, V>> {
+        // SAFETY: every access goes through the tree, so the node outlives
+        // the borrow of the pointer
+        unsafe { self.as_ref() }
+    }
+
+    fn node_mut(&mut self) -> Option<&mut Node<K, V>> {
+        // SAFETY: the node is live as in `node`, and the reference is only
+        // used for a single field write while no other reference exists
+        unsafe { self.0.as_mut() }
+    }
+
+    // SAFETY: the pointer must be non-null and nothing may use it afterwards
+    unsafe fn into_box(self) -> Box<Node<K, V>> {
+        Box::from_raw(self.0)
+    }
+
     fn is_red(&self) -> bool {
-        if self.is_null() {
-            false
-        } else {
-            unsafe { (*self.0).color == Color::Red }
-        }
+        self.color() == Color::Red
     }
 
     fn color(&self) -> Color {
-        if self.is_null() {
-            return Color::Black;
-        }
-        unsafe { (*self.0).color }
+        self.node().map_or(Color::Black, |node| node.color)
     }
 
     fn set_color(&mut self, color: Color) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.color = color;
         }
-        unsafe { (*self.0).color = color }
     }
 
     fn count(&self) -> usize {
-        if self.is_null() {
-            0
-        } else {
-            unsafe { (*self.0).count }
-        }
+        self.node().map_or(0, |node| node.count)
     }
 
     fn set_count(&mut self, count: usize) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.count = count;
         }
-        unsafe { (*self.0).count = count }
     }
 
     fn left(&self) -> Self {
-        if self.is_null() {
-            return NodePtr::null();
-        }
-        unsafe { (*self.0).left }
+        self.node().map_or(NodePtr::null(), |node| node.left)
     }
 
     fn set_left(&mut self, left: NodePtr<K, V>) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.left = left;
         }
-        unsafe { (*self.0).left = left }
     }
 
     fn right(&self) -> Self {
-        if self.is_null() {
-            return NodePtr::null();
-        }
-        unsafe { (*self.0).right }
+        self.node().map_or(NodePtr::null(), |node| node.right)
     }
 
     fn set_right(&mut self, right: NodePtr<K, V>) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.right = right;
         }
-        unsafe { (*self.0).right = right }
     }
 
     fn key(&self) -> Option<&K> {
-        if self.is_null() {
-            return None;
-        }
-        unsafe { Some(&(*self.0).key) }
+        self.node().map(|node| &node.key)
     }
 
     fn set_value(&mut self, value: V) {
-        if self.is_null() {
-            return;
+        if let Some(node) = self.node_mut() {
+            node.value = value;
         }
-        let mut node = unsafe { Box::from_raw(self.0) };
-        let _old = core::mem::replace(&mut node.value, value);
-        let _ = Box::into_raw(node);
     }
 
     fn get_node_ptr(&self, key: &K) -> Self {
@@ -167,7 +190,7 @@ impl<K: Ord, V> NodePtr<K, V> {
             Some(k) => match key.cmp(k) {
                 Ordering::Less => self.left().get_node_ptr(key),
                 Ordering::Greater => self.right().get_node_ptr(key),
-                Ordering::Equal => self.clone(),
+                Ordering::Equal => *self,
             },
         }
     }
@@ -195,13 +218,13 @@ impl<K: Ord, V> NodePtr<K, V> {
             NodePtr::flip_colors(h);
         }
         h.set_count(1 + h.left().count() + h.right().count());
-        h.clone()
+        *h
     }
 
     fn rotate_left(h: &mut NodePtr<K, V>) -> NodePtr<K, V> {
         let mut x = h.right();
         h.set_right(x.left());
-        x.set_left(h.clone());
+        x.set_left(*h);
         x.set_color(h.color());
         h.set_color(Color::Red);
         x.set_count(h.count());
@@ -212,7 +235,7 @@ impl<K: Ord, V> NodePtr<K, V> {
     fn rotate_right(h: &mut NodePtr<K, V>) -> NodePtr<K, V> {
         let mut x = h.left();
         h.set_left(x.right());
-        x.set_right(h.clone());
+        x.set_right(*h);
         x.set_color(h.color());
         h.set_color(Color::Red);
         x.set_count(h.count());
@@ -228,11 +251,11 @@ impl<K: Ord, V> NodePtr<K, V> {
 
     fn clear(&mut self) {
         if !self.is_null() {
-            unsafe {
-                self.left().clear();
-                self.right().clear();
-                Box::from_raw(self.0);
-            }
+            self.left().clear();
+            self.right().clear();
+            // SAFETY: the subtrees are freed and the tree is being dropped,
+            // so this is the last use of the node
+            drop(unsafe { self.into_box() });
         }
     }
 }
@@ -255,23 +278,24 @@ where
     V: ToString,
 {
     fn pair(&self) -> (&K, &V) {
-        unsafe { (&(*self.0).key, &(*self.0).value) }
+        let node = self.node().unwrap();
+        (&node.key, &node.value)
     }
 
     fn inorder_tree_walk_string(&self) -> String {
         let mut result: String = "".into();
-        result.push_str("(");
+        result.push('(');
 
         if !self.is_null() {
             let (key, value) = self.pair();
             result.push_str(&key.to_string());
-            result.push_str(":");
+            result.push(':');
             result.push_str(&value.to_string());
-            result.push_str(":");
+            result.push(':');
             if self.is_red() {
-                result.push_str("r");
+                result.push('r');
             } else {
-                result.push_str("b");
+                result.push('b');
             }
 
             let left_str = self.left().inorder_tree_walk_string();
@@ -281,7 +305,7 @@ where
             result.push_str(&right_str);
         }
 
-        result.push_str(")");
+        result.push(')');
         result
     }
 }
@@ -289,6 +313,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::structure::drop_counter::DropCounter;
 
     #[test]
     fn test_get() {
@@ -370,4 +395,32 @@ mod tests {
         rbtree.put('X', 10);
         assert_eq!(rbtree.inorder_tree_walk_string(), "(H:4:b(C:2:b(A:1:b()())(E:3:b()()))(R:8:b(M:6:r(L:5:b()())(P:7:b()()))(X:10:b(S:9:r()())())))");
     }
+
+    #[test]
+    fn test_min_max_key() {
+        let mut rbtree: RedBlackTree<i32, ()> = RedBlackTree::new();
+        assert_eq!(rbtree.min_key(), None);
+        assert_eq!(rbtree.max_key(), None);
+        for &k in &[5, 3, 8, 1, 9, 7] {
+            rbtree.put(k, ());
+        }
+        assert_eq!(rbtree.min_key(), Some(&1));
+        assert_eq!(rbtree.max_key(), Some(&9));
+    }
+
+    #[test]
+    fn test_drops_each_value_once() {
+        let counter = DropCounter::new();
+        let mut rbtree = RedBlackTree::new();
+        for id in 0..20 {
+            rbtree.put(id % 10, counter.value(id));
+        }
+        // replacing a value drops the old one right away
+        assert_eq!(counter.count(), 10);
+        assert!((0..10).all(|id| counter.dropped(id)));
+        assert_eq!(rbtree.get(&3).map(|v| v.id), Some(13));
+        assert_eq!(rbtree.len(), 10);
+        drop(rbtree);
+        counter.assert_all_dropped_once(20);
+    }
 }
@@ -9,9 +9,15 @@ struct Node<T> {
     next: NodePtr<T>,
 }
 
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Drop for Stack<T> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        while self.pop().is_some() {}
     }
 }
 
@@ -20,6 +26,10 @@ impl<T> Stack<T> {
         Stack { head: NodePtr::null() }
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.head.is_null()
+    }
+
     pub fn push(&mut self, elem: T) {
         self.head.push(elem)
     }
@@ -39,12 +49,15 @@ impl<T> Stack<T> {
 
 impl<T> Clone for NodePtr<T> {
     fn clone(&self) -> NodePtr<T> {
-        Self(self.0)
+        *self
     }
 }
 
 impl<T> Copy for NodePtr<T> {}
 
+// every non-null `NodePtr` points to a node allocated by `NodePtr::new` that
+// is owned by exactly one stack and freed only through `into_box`, references
+// into a node never outlive the borrow of the stack they came from
 impl<T> NodePtr<T> {
     fn null() -> NodePtr<T> {
         Self(core::ptr::null_mut())
@@ -66,37 +79,37 @@ impl<T> NodePtr<T> {
         Self(Box::into_raw(Box::new(node)))
     }
 
-    fn elem(&self) -> Option<&T> {
-        if self.is_null() {
-            return None;
-        }
-        unsafe { Some(&(*self.0).elem) }
+    fn node(&self) -> Option<&Node<T>> {
+        // SAFETY: the pointer is null or points to a live node, and the
+        // stack is borrowed for as long as the reference lives
+        unsafe { self.0.as_ref() }
     }
 
-    fn elem_mut(&mut self) -> Option<&mut T> {
-        if self.is_null() {
-            return None;
-        }
-        unsafe { Some(& mut(*self.0).elem) }
+    fn node_mut(&mut self) -> Option<&mut Node<T>> {
+        // SAFETY: as in `node`, the stack is mutably borrowed so no other
+        // reference to the node exists
+        unsafe { self.0.as_mut() }
     }
 
-    fn next(&self) -> Self {
-        if self.is_null() {
-            return Self::null();
-        }
-        unsafe { (*self.0).next }
+    // SAFETY: the pointer must be non-null and the caller must unlink the
+    // node, so nothing reads or frees it afterwards
+    unsafe fn into_box(self) -> Box<Node<T>> {
+        Box::from_raw(self.0)
     }
 
-    fn set_next(&mut self, next: NodePtr<T>) {
-        if self.is_null() {
-            return;
-        }
-        unsafe { (*self.0).next = next }
+    fn elem(&self) -> Option<&T> {
+        self.node().map(|node| &node.elem)
+    }
+
+    fn elem_mut(&mut self) -> Option<&mut T> {
+        self.node_mut().map(|node| &mut node.elem)
     }
 
     fn push(&mut self, elem: T) {
         let mut node = Self::new(elem);
-        node.set_next(self.clone());
+        if let Some(new) = node.node_mut() {
+            new.next = *self;
+        }
         self.set(node);
     }
 
@@ -104,9 +117,10 @@ impl<T> NodePtr<T> {
         if self.is_null() {
             return None;
         }
-        let node = unsafe { Box::from_raw(self.0) };
-        let next = self.next();
-        self.set(next);
+        // SAFETY: the head is non-null and is replaced by its successor
+        // before anything else can reach it
+        let node = unsafe { self.into_box() };
+        self.set(node.next);
         Some(node.elem)
     }
 }
@@ -114,6 +128,7 @@ impl<T> NodePtr<T> {
 #[cfg(test)]
 mod test {
     use super::Stack;
+    use crate::structure::drop_counter::DropCounter;
 
     #[test]
     fn basics() {
@@ -128,4 +143,40 @@ mod test {
         assert_eq!(stack.pop(), Some(1));
         assert_eq!(stack.pop(), None);
     }
+
+    #[test]
+    fn peek_mut() {
+        let mut stack = Stack::new();
+        assert_eq!(stack.peek_mut(), None);
+        stack.push(1);
+        stack.push(2);
+        *stack.peek_mut().unwrap() = 20;
+        assert_eq!(stack.pop(), Some(20));
+        assert_eq!(stack.pop(), Some(1));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn drops_each_value_once() {
+        let counter = DropCounter::new();
+        let mut stack = Stack::new();
+        for id in 0..10 {
+            stack.push(counter.value(id));
+        }
+        assert_eq!(stack.pop().map(|x| x.id), Some(9));
+        assert!(counter.dropped(9));
+        *stack.peek_mut().unwrap() = counter.value(10);
+        assert!(counter.dropped(8));
+        assert_eq!(counter.count(), 2);
+        drop(stack);
+        counter.assert_all_dropped_once(11);
+    }
+
+    #[test]
+    fn long_stack_drop() {
+        let mut stack = Stack::new();
+        for i in 0..10_000 {
+            stack.push(i);
+        }
+    }
 }
@@ -0,0 +1,619 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::Range;
+use std::slice;
+use std::sync::Arc;
+
+const BITS: usize = 5;
+const BRANCH: usize = 1 << BITS;
+// extra nodes allowed over the optimum before concat rebalances a level
+const EXTRA: usize = 2;
+
+pub struct Vector<T> {
+    root: Arc<Node<T>>,
+    shift: usize,
+}
+
+enum Node<T> {
+    Leaf(Vec<T>),
+    Branch {
+        children: Vec<Arc<Node<T>>>,
+        // cumulative child lengths, only kept for relaxed nodes
+        sizes: Option<Vec<usize>>,
+        len: usize,
+    },
+}
+
+impl<T> Node<T> {
+    fn branch(children: Vec<Arc<Node<T>>>, shift: usize) -> Node<T> {
+        let len = children.iter().map(|child| child.len()).sum();
+        let strict = children
+            .iter()
+            .rev()
+            .skip(1)
+            .all(|child| child.len() == 1 << shift);
+        let sizes = if strict {
+            None
+        } else {
+            Some(
+                children
+                    .iter()
+                    .scan(0, |acc, child| {
+                        *acc += child.len();
+                        Some(*acc)
+                    })
+                    .collect(),
+            )
+        };
+        Node::Branch {
+            children,
+            sizes,
+            len,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(items) => items.len(),
+            Node::Branch { len, .. } => *len,
+        }
+    }
+
+    // number of items or children held directly by this node
+    fn slots(&self) -> usize {
+        match self {
+            Node::Leaf(items) => items.len(),
+            Node::Branch { children, .. } => children.len(),
+        }
+    }
+
+    fn children(&self) -> &[Arc<Node<T>>] {
+        match self {
+            Node::Leaf(_) => &[],
+            Node::Branch { children, .. } => children,
+        }
+    }
+
+    // child slot holding `index` and the index inside that child
+    fn position(&self, shift: usize, index: usize) -> (usize, usize) {
+        match self {
+            Node::Branch { sizes: None, .. } => (index >> shift, index & ((1 << shift) - 1)),
+            Node::Branch {
+                sizes: Some(sizes), ..
+            } => {
+                let mut slot = index >> shift;
+                while sizes[slot] <= index {
+                    slot += 1;
+                }
+                let before = if slot == 0 { 0 } else { sizes[slot - 1] };
+                (slot, index - before)
+            }
+            Node::Leaf(_) => unreachable!("leaves have no child slots"),
+        }
+    }
+
+    fn get(&self, shift: usize, index: usize) -> &T {
+        match self {
+            Node::Leaf(items) => &items[index],
+            Node::Branch { children, .. } => {
+                let (slot, index) = self.position(shift, index);
+                children[slot].get(shift - BITS, index)
+            }
+        }
+    }
+
+    fn new_path(shift: usize, elem: T) -> Node<T> {
+        if shift == 0 {
+            Node::Leaf(vec![elem])
+        } else {
+            Node::branch(vec![Arc::new(Node::new_path(shift - BITS, elem))], shift)
+        }
+    }
+}
+
+impl<T: Clone> Node<T> {
+    fn set(&self, shift: usize, index: usize, elem: T) -> Node<T> {
+        match self {
+            Node::Leaf(items) => {
+                let mut items = items.clone();
+                items[index] = elem;
+                Node::Leaf(items)
+            }
+            Node::Branch {
+                children,
+                sizes,
+                len,
+            } => {
+                let (slot, sub) = self.position(shift, index);
+                let mut children = children.clone();
+                children[slot] = Arc::new(children[slot].set(shift - BITS, sub, elem));
+                Node::Branch {
+                    children,
+                    sizes: sizes.clone(),
+                    len: *len,
+                }
+            }
+        }
+    }
+
+    // gives the element back when the rightmost path has no room left
+    fn push(&self, shift: usize, elem: T) -> Result<Node<T>, T> {
+        match self {
+            Node::Leaf(items) => {
+                if items.len() == BRANCH {
+                    return Err(elem);
+                }
+                let mut items = items.clone();
+                items.push(elem);
+                Ok(Node::Leaf(items))
+            }
+            Node::Branch { children, .. } => {
+                let mut children = children.clone();
+                match children.last().unwrap().push(shift - BITS, elem) {
+                    Ok(last) => *children.last_mut().unwrap() = Arc::new(last),
+                    Err(elem) => {
+                        if children.len() == BRANCH {
+                            return Err(elem);
+                        }
+                        children.push(Arc::new(Node::new_path(shift - BITS, elem)));
+                    }
+                }
+                Ok(Node::branch(children, shift))
+            }
+        }
+    }
+
+    // keeps the first `n` elements, 0 < n <= len
+    fn take(&self, shift: usize, n: usize) -> Node<T> {
+        match self {
+            Node::Leaf(items) => Node::Leaf(items[..n].to_vec()),
+            Node::Branch { children, .. } => {
+                let (slot, sub) = self.position(shift, n - 1);
+                let mut kept = children[..slot].to_vec();
+                kept.push(Arc::new(children[slot].take(shift - BITS, sub + 1)));
+                Node::branch(kept, shift)
+            }
+        }
+    }
+
+    // drops the first `n` elements, n < len
+    fn skip(&self, shift: usize, n: usize) -> Node<T> {
+        match self {
+            Node::Leaf(items) => Node::Leaf(items[n..].to_vec()),
+            Node::Branch { children, .. } => {
+                let (slot, sub) = self.position(shift, n);
+                let mut kept = vec![Arc::new(children[slot].skip(shift - BITS, sub))];
+                kept.extend_from_slice(&children[slot + 1..]);
+                Node::branch(kept, shift)
+            }
+        }
+    }
+
+    // merges two nodes of the same height into one or two nodes of that height
+    fn merge(left: &Arc<Node<T>>, right: &Arc<Node<T>>, shift: usize) -> Vec<Arc<Node<T>>> {
+        if shift == 0 {
+            if left.len() + right.len() > BRANCH {
+                return vec![left.clone(), right.clone()];
+            }
+            let mut items = Vec::with_capacity(left.len() + right.len());
+            for leaf in &[left, right] {
+                if let Node::Leaf(xs) = &***leaf {
+                    items.extend_from_slice(xs);
+                }
+            }
+            return vec![Arc::new(Node::Leaf(items))];
+        }
+
+        let (left_children, right_children) = (left.children(), right.children());
+        let (left_last, left_rest) = left_children.split_last().unwrap();
+        let (right_first, right_rest) = right_children.split_first().unwrap();
+
+        let mut all = left_rest.to_vec();
+        all.extend(Node::merge(left_last, right_first, shift - BITS));
+        all.extend_from_slice(right_rest);
+        let all = Node::rebalance(all, shift - BITS);
+
+        if all.len() <= BRANCH {
+            vec![Arc::new(Node::branch(all, shift))]
+        } else {
+            let right = all[BRANCH..].to_vec();
+            let left = all[..BRANCH].to_vec();
+            vec![
+                Arc::new(Node::branch(left, shift)),
+                Arc::new(Node::branch(right, shift)),
+            ]
+        }
+    }
+
+    // repacks sibling nodes densely once they exceed the optimal count by EXTRA
+    fn rebalance(nodes: Vec<Arc<Node<T>>>, shift: usize) -> Vec<Arc<Node<T>>> {
+        let total: usize = nodes.iter().map(|node| node.slots()).sum();
+        let optimal = total.div_ceil(BRANCH);
+        if nodes.len() <= optimal + EXTRA {
+            return nodes;
+        }
+
+        if shift == 0 {
+            let items: Vec<T> = nodes
+                .iter()
+                .flat_map(|node| match &**node {
+                    Node::Leaf(items) => items.iter().cloned(),
+                    Node::Branch { .. } => [].iter().cloned(),
+                })
+                .collect();
+            items
+                .chunks(BRANCH)
+                .map(|chunk| Arc::new(Node::Leaf(chunk.to_vec())))
+                .collect()
+        } else {
+            let children: Vec<Arc<Node<T>>> = nodes
+                .iter()
+                .flat_map(|node| node.children().iter().cloned())
+                .collect();
+            children
+                .chunks(BRANCH)
+                .map(|chunk| Arc::new(Node::branch(chunk.to_vec(), shift)))
+                .collect()
+        }
+    }
+}
+
+impl<T> Vector<T> {
+    pub fn new() -> Self {
+        Vector {
+            root: Arc::new(Node::Leaf(vec![])),
+            shift: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.root.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(self.root.get(self.shift, index))
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &*self.root {
+            Node::Leaf(items) => Iter {
+                stack: vec![],
+                leaf: items.iter(),
+            },
+            Node::Branch { children, .. } => Iter {
+                stack: vec![children.iter()],
+                leaf: [].iter(),
+            },
+        }
+    }
+
+    // drops root levels that only have a single child
+    fn trimmed(mut root: Arc<Node<T>>, mut shift: usize) -> Vector<T> {
+        while shift > 0 && root.slots() == 1 {
+            root = root.children()[0].clone();
+            shift -= BITS;
+        }
+        Vector { root, shift }
+    }
+
+    // wraps `node` in single-child branches until it reaches height `to`
+    fn lift(mut node: Arc<Node<T>>, mut shift: usize, to: usize) -> Arc<Node<T>> {
+        while shift < to {
+            shift += BITS;
+            node = Arc::new(Node::branch(vec![node], shift));
+        }
+        node
+    }
+}
+
+impl<T: Clone> Vector<T> {
+    pub fn set(&self, index: usize, elem: T) -> Option<Vector<T>> {
+        if index >= self.len() {
+            return None;
+        }
+        Some(Vector {
+            root: Arc::new(self.root.set(self.shift, index, elem)),
+            shift: self.shift,
+        })
+    }
+
+    pub fn push_back(&self, elem: T) -> Vector<T> {
+        match self.root.push(self.shift, elem) {
+            Ok(root) => Vector {
+                root: Arc::new(root),
+                shift: self.shift,
+            },
+            Err(elem) => {
+                let path = Arc::new(Node::new_path(self.shift, elem));
+                let shift = self.shift + BITS;
+                Vector {
+                    root: Arc::new(Node::branch(vec![self.root.clone(), path], shift)),
+                    shift,
+                }
+            }
+        }
+    }
+
+    pub fn pop_back(&self) -> Option<(Vector<T>, T)> {
+        let last = self.last()?.clone();
+        Some((self.slice(0..self.len() - 1), last))
+    }
+
+    pub fn slice(&self, range: Range<usize>) -> Vector<T> {
+        assert!(range.start <= range.end, "slice start is after its end");
+        assert!(range.end <= self.len(), "slice end is out of bounds");
+        if range.start == range.end {
+            return Vector::new();
+        }
+
+        let mut root = self.root.clone();
+        if range.end < self.len() {
+            root = Arc::new(root.take(self.shift, range.end));
+        }
+        if range.start > 0 {
+            root = Arc::new(root.skip(self.shift, range.start));
+        }
+        Vector::trimmed(root, self.shift)
+    }
+
+    pub fn concat(&self, other: &Vector<T>) -> Vector<T> {
+        if self.is_empty() {
+            return other.clone();
+        }
+        if other.is_empty() {
+            return self.clone();
+        }
+
+        let shift = self.shift.max(other.shift);
+        let left = Vector::lift(self.root.clone(), self.shift, shift);
+        let right = Vector::lift(other.root.clone(), other.shift, shift);
+        let merged = Node::merge(&left, &right, shift);
+        if merged.len() == 1 {
+            Vector::trimmed(merged[0].clone(), shift)
+        } else {
+            Vector {
+                root: Arc::new(Node::branch(merged, shift + BITS)),
+                shift: shift + BITS,
+            }
+        }
+    }
+}
+
+impl<T> Default for Vector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for Vector<T> {
+    fn clone(&self) -> Self {
+        Vector {
+            root: self.root.clone(),
+            shift: self.shift,
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Vector<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for Vector<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Vector<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// builds a dense tree bottom up
+impl<T> FromIterator<T> for Vector<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut iter = iter.into_iter();
+        let mut nodes = vec![];
+        loop {
+            let items: Vec<T> = iter.by_ref().take(BRANCH).collect();
+            if items.is_empty() {
+                break;
+            }
+            nodes.push(Arc::new(Node::Leaf(items)));
+        }
+        if nodes.is_empty() {
+            return Vector::new();
+        }
+
+        let mut shift = 0;
+        while nodes.len() > 1 {
+            shift += BITS;
+            nodes = nodes
+                .chunks(BRANCH)
+                .map(|chunk| Arc::new(Node::branch(chunk.to_vec(), shift)))
+                .collect();
+        }
+        Vector {
+            root: nodes.pop().unwrap(),
+            shift,
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<slice::Iter<'a, Arc<Node<T>>>>,
+    leaf: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(elem) = self.leaf.next() {
+                return Some(elem);
+            }
+            let node = loop {
+                match self.stack.last_mut()?.next() {
+                    Some(node) => break node,
+                    None => {
+                        self.stack.pop();
+                    }
+                }
+            };
+            match &**node {
+                Node::Leaf(items) => self.leaf = items.iter(),
+                Node::Branch { children, .. } => self.stack.push(children.iter()),
+            }
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Vector<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check<T: PartialEq + fmt::Debug>(vector: &Vector<T>, expected: &[T]) {
+        assert_eq!(vector.len(), expected.len());
+        for (i, x) in expected.iter().enumerate() {
+            assert_eq!(vector.get(i), Some(x));
+        }
+        assert_eq!(vector.get(expected.len()), None);
+        assert!(vector.iter().eq(expected.iter()));
+    }
+
+    #[test]
+    fn test_push_get() {
+        let mut vector = Vector::new();
+        assert!(vector.is_empty());
+        assert_eq!(vector.get(0), None);
+        for i in 0..3000 {
+            vector = vector.push_back(i);
+        }
+        check(&vector, &(0..3000).collect::<Vec<_>>());
+        assert_eq!(vector.first(), Some(&0));
+        assert_eq!(vector.last(), Some(&2999));
+    }
+
+    #[test]
+    fn test_persistence() {
+        let v1: Vector<i32> = (0..100).collect();
+        let v2 = v1.set(40, -1).unwrap();
+        let v3 = v2.push_back(100);
+        let (v4, last) = v3.pop_back().unwrap();
+
+        check(&v1, &(0..100).collect::<Vec<_>>());
+        assert_eq!(v2.get(40), Some(&-1));
+        assert_eq!(v1.get(40), Some(&40));
+        assert_eq!(v3.len(), 101);
+        assert_eq!(last, 100);
+        assert_eq!(v4, v2);
+        assert!(v1.set(100, 0).is_none());
+        assert!(Vector::<i32>::new().pop_back().is_none());
+    }
+
+    #[test]
+    fn test_pop_back() {
+        let mut vector: Vector<i32> = (0..1100).collect();
+        for i in (0..1100).rev() {
+            let (rest, last) = vector.pop_back().unwrap();
+            assert_eq!(last, i);
+            vector = rest;
+        }
+        assert!(vector.is_empty());
+    }
+
+    #[test]
+    fn test_slice() {
+        let xs: Vec<i32> = (0..2000).collect();
+        let vector: Vector<i32> = xs.iter().cloned().collect();
+        for &(start, end) in &[
+            (0, 0),
+            (0, 2000),
+            (1, 2000),
+            (0, 1999),
+            (33, 1057),
+            (1024, 1025),
+        ] {
+            check(&vector.slice(start..end), &xs[start..end]);
+        }
+        let sliced = vector.slice(500..1500).slice(10..900);
+        check(&sliced, &xs[510..1400]);
+        check(
+            &sliced.push_back(-1).set(0, -2).unwrap().slice(0..3),
+            &[-2, 511, 512],
+        );
+    }
+
+    #[test]
+    fn test_concat() {
+        let mut expected = vec![];
+        let mut vector = Vector::new();
+        for i in 0..200 {
+            let piece: Vec<usize> = (0..i * 7 % 45).map(|x| x + i).collect();
+            expected.extend_from_slice(&piece);
+            vector = vector.concat(&piece.into_iter().collect());
+        }
+        check(&vector, &expected);
+        // relaxed nodes keep the tree shallow
+        assert!(vector.shift <= 3 * BITS);
+
+        let doubled = vector.concat(&vector);
+        let mut twice = expected.clone();
+        twice.extend_from_slice(&expected);
+        check(&doubled, &twice);
+        let updated = doubled.set(expected.len(), 0).unwrap().push_back(7);
+        twice[expected.len()] = 0;
+        twice.push(7);
+        check(&updated, &twice);
+    }
+
+    #[test]
+    fn test_concat_slices() {
+        let xs: Vec<i32> = (0..5000).collect();
+        let vector: Vector<i32> = xs.iter().cloned().collect();
+        let mut expected = vec![];
+        let mut joined = Vector::new();
+        let mut start = 0;
+        while start < xs.len() {
+            let end = (start + start % 97 + 1).min(xs.len());
+            expected.extend_from_slice(&xs[start..end]);
+            joined = joined.concat(&vector.slice(start..end));
+            start = end;
+        }
+        check(&joined, &expected);
+        check(&joined.slice(1234..4321), &expected[1234..4321]);
+    }
+
+    #[test]
+    fn test_eq_debug() {
+        let a: Vector<i32> = (1..=3).collect();
+        let b = Vector::new().push_back(1).push_back(2).push_back(3);
+        assert_eq!(a, b);
+        assert_eq!(format!("{:?}", a), "[1, 2, 3]");
+        assert_eq!(a.clone().concat(&Vector::new()), b);
+    }
+}